@@ -20,8 +20,8 @@ pub async fn main() -> Result<(), MiniRedisClientError> {
     let mut client = client::connect("127.0.0.1:6379").await?;
 
     // Set the key "hello" with value "world"
-    let result = client.set("hello", "world".into()).await?;
-    println!("set value to the server success， result: {:?}", result);
+    client.set("hello", "world".into()).await?;
+    println!("set value to the server success");
 
     // Get key "hello"
     let result = client.get("hello").await?;
@@ -59,6 +59,14 @@ async fn main() -> Result<(), MiniRedisClientError> {
                 println!("{:?}", value);
             }
         }
+        Command::Echo { msg } => {
+            let value = client.echo(msg).await?;
+            if let Ok(string) = std::str::from_utf8(&value) {
+                println!("\"{}\"", string);
+            } else {
+                println!("{:?}", value);
+            }
+        }
         Command::Get { key } => {
             if let Some(value) = client.get(&key).await? {
                 if let Ok(string) = std::str::from_utf8(&value) {
@@ -86,6 +94,10 @@ async fn main() -> Result<(), MiniRedisClientError> {
             client.set_expires(&key, value, expires).await?;
             println!("OK");
         }
+        Command::DbSize => {
+            let size = client.db_size().await?;
+            println!("(integer) {}", size);
+        }
         Command::Publish { channel, message } => {
             client.publish(&channel, message).await?;
             println!("Publish OK");
@@ -6,12 +6,15 @@
 //!
 //! The `clap` crate is used for parsing arguments.
 
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
 use clap::Parser;
 use dotenv::dotenv;
-use tokio::net::TcpListener;
 use tokio::signal;
 
-use mini_redis::consts::DEFAULT_PORT;
+use mini_redis::config::ServerConfig;
+use mini_redis::consts::{DEFAULT_PORT, DEFAULT_SHUTDOWN_TIMEOUT, MAX_CONNECTIONS};
 use mini_redis::error::MiniRedisServerError;
 use mini_redis::{logger, server};
 
@@ -23,8 +26,21 @@ use mini_redis::{logger, server};
     about = "A mini redis server"
 )]
 struct Cli {
+    /// Address to listen on. Use `::` to accept both IPv6 and IPv4
+    /// connections.
+    #[clap(long, default_value = "0.0.0.0")]
+    bind: IpAddr,
+
     #[clap(long)]
     port: Option<u16>,
+
+    /// Maximum number of connections served at once.
+    #[clap(long, default_value_t = MAX_CONNECTIONS, parse(try_from_str = max_connections_from_str))]
+    max_connections: usize,
+
+    /// Seconds to wait for active connections to terminate on shutdown.
+    #[clap(long, default_value_t = DEFAULT_SHUTDOWN_TIMEOUT.as_secs())]
+    shutdown_timeout_secs: u64,
 }
 
 #[tokio::main]
@@ -33,13 +49,40 @@ pub async fn main() -> Result<(), MiniRedisServerError> {
     let port = cli.port.unwrap_or(DEFAULT_PORT);
 
     // Bind a TCP listener
-    let listener = TcpListener::bind(&format!("0.0.0.0:{}", port)).await?;
+    let listener = server::bind(SocketAddr::new(cli.bind, port))?;
 
-    server::run(listener, signal::ctrl_c()).await;
+    // Shut down gracefully on SIGINT, and on SIGTERM as sent by service
+    // managers.
+    let mut signals: Vec<server::ShutdownSignal> = vec![Box::pin(async {
+        let _ = signal::ctrl_c().await;
+    })];
+    #[cfg(unix)]
+    {
+        let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate())?;
+        signals.push(Box::pin(async move {
+            terminate.recv().await;
+        }));
+    }
+
+    let config = ServerConfig {
+        max_connections: Some(cli.max_connections),
+        shutdown_timeout: Some(Duration::from_secs(cli.shutdown_timeout_secs)),
+        ..Default::default()
+    };
+    server::run_with_config(listener, server::any_signal(signals), config).await?;
 
     Ok(())
 }
 
+/// Parse `--max-connections`, rejecting 0 which would never serve anyone.
+fn max_connections_from_str(src: &str) -> Result<usize, String> {
+    match src.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".into()),
+        Ok(max) => Ok(max),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
 fn init() -> Cli {
     dotenv().ok();
     logger::init();
@@ -3,15 +3,37 @@
 //! Provides an async connect and methods for issuing the supported commands.
 
 use crate::client::subscriber::Subscriber;
+use crate::cmd::append::Append;
+use crate::cmd::client_list::ClientList;
+use crate::cmd::cluster::Cluster;
+use crate::cmd::dbsize::DbSize;
+use crate::cmd::del::Del;
+use crate::cmd::echo::Echo;
+use crate::cmd::exists::Exists;
+use crate::cmd::expire::PExpire;
+use crate::cmd::flushdb::FlushDb;
 use crate::cmd::get::Get;
+use crate::cmd::getset::GetSet;
+use crate::cmd::incr::{Decr, DecrBy, Incr, IncrBy};
+use crate::cmd::keys::Keys;
+use crate::cmd::mget::Mget;
 use bytes::Bytes;
 use log::{debug, error};
 use std::time::Duration;
 
+use crate::cmd::mpublish::MPublish;
+use crate::cmd::mset::Mset;
+use crate::cmd::persist::Persist;
 use crate::cmd::ping::Ping;
 use crate::cmd::publish::Publish;
+use crate::cmd::rename::{Rename, RenameNx};
+use crate::cmd::scan::Scan;
 use crate::cmd::set::Set;
+use crate::cmd::setnx::SetNx;
+use crate::cmd::strlen::Strlen;
 use crate::cmd::subscribe::Subscribe;
+use crate::cmd::ttl::{Pttl, Ttl};
+use crate::cmd::type_cmd::Type;
 use crate::connection::connect::Connection;
 use crate::connection::frame::Frame;
 use crate::error::MiniRedisConnectionError;
@@ -28,9 +50,10 @@ pub struct Client {
     /// implemented using a buffered `TcpStream`.
     ///
     /// When `Listener` receives an inbound connection, the `TcpStream` is
-    /// passed to `Connection::new`, which initializes the associated buffers.
-    /// `Connection` allows the handler to operate at the "frame" level and keep
-    /// the byte level protocol parsing details encapsulated in `Connection`.
+    /// passed to `Connection::with_capacity`, which initializes the associated
+    /// buffers. `Connection` allows the handler to operate at the "frame" level
+    /// and keep the byte level protocol parsing details encapsulated in
+    /// `Connection`.
     pub(crate) connection: Connection,
 }
 
@@ -69,6 +92,37 @@ impl Client {
         }
     }
 
+    /// Returns `msg` as echoed by the server.
+    ///
+    /// Unlike `ping`, the message is always sent and echoed, which is useful
+    /// for protocol-level testing and latency checks.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let echo = client.echo("hello".into()).await.unwrap();
+    ///     assert_eq!(b"hello", &echo[..]);
+    /// }
+    /// ```
+    pub async fn echo(&mut self, msg: Bytes) -> Result<Bytes, MiniRedisConnectionError> {
+        let frame = Echo::new(msg).into_frame()?;
+
+        debug!("echo command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Bulk(value) => Ok(value),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
     /// Get the value of key.
     ///
     /// If the key does not exist the special value `None` is returned.
@@ -108,13 +162,51 @@ impl Client {
         }
     }
 
-    /// Set `key` to hold the given `value`.
+    /// Get the values of several keys in one round trip.
     ///
-    /// The `value` is associated with `key` until it is overwritten by the next
-    /// call to `set` or it is removed.
+    /// Returns one entry per key, in order, `None` for the keys which do not
+    /// exist.
     ///
-    /// If key already holds a value, it is overwritten. Any previous time to
-    /// live associated with the key is discarded on successful SET operation.
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let values = client.mget(&["foo", "bar"]).await.unwrap();
+    ///     println!("Got = {:?}", values);
+    /// }
+    /// ```
+    pub async fn mget(
+        &mut self,
+        keys: &[&str],
+    ) -> Result<Vec<Option<Bytes>>, MiniRedisConnectionError> {
+        let frame = Mget::new(keys).into_frame()?;
+
+        debug!("mget command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Array(frames) => frames
+                .into_iter()
+                .map(|frame| match frame {
+                    Frame::Bulk(value) => Ok(Some(value)),
+                    Frame::Null => Ok(None),
+                    frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+                })
+                .collect(),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Renames `key` to `new_key`, overwriting any value of `new_key`.
+    ///
+    /// The time to live of `key` moves along with its value. Fails if `key`
+    /// does not exist.
     ///
     /// # Examples
     ///
@@ -126,94 +218,218 @@ impl Client {
     ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
     ///
     ///     client.set("foo", "bar".into()).await.unwrap();
-    ///
-    ///     // Getting the value immediately works
-    ///     let val = client.get("foo").await.unwrap().unwrap();
-    ///     assert_eq!(val, "bar");
+    ///     client.rename("foo", "baz").await.unwrap();
     /// }
     /// ```
-    pub async fn set(&mut self, key: &str, value: Bytes) -> Result<(), MiniRedisConnectionError> {
-        // Create a `Set` command and pass it to `set_cmd`. A separate method is
-        // used to set a value with an expiration. The common parts of both
-        // functions are implemented by `set_cmd`.
-        self.set_cmd(Set::new(key, value, None)).await
+    pub async fn rename(
+        &mut self,
+        key: &str,
+        new_key: &str,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let frame = Rename::new(key, new_key).into_frame()?;
+
+        debug!("rename command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Simple(response) if response == "OK" => Ok(()),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
     }
 
-    /// Set `key` to hold the given `value`. The value expires after `expiration`
+    /// Renames `key` to `new_key`, only if `new_key` does not exist.
     ///
-    /// The `value` is associated with `key` until one of the following:
-    /// - it expires.
-    /// - it is overwritten by the next call to `set`.
-    /// - it is removed.
+    /// Returns `true` if the key was renamed, `false` if `new_key` already
+    /// held a value. Fails if `key` does not exist.
     ///
-    /// If key already holds a value, it is overwritten. Any previous time to
-    /// live associated with the key is discarded on a successful SET operation.
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///     let renamed = client.rename_nx("foo", "baz").await.unwrap();
+    ///     println!("Renamed = {}", renamed);
+    /// }
+    /// ```
+    pub async fn rename_nx(
+        &mut self,
+        key: &str,
+        new_key: &str,
+    ) -> Result<bool, MiniRedisConnectionError> {
+        let frame = RenameNx::new(key, new_key).into_frame()?;
+
+        debug!("renamenx command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(1) => Ok(true),
+            Frame::Integer(0) => Ok(false),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Set `key` to hold `value`, only if the key does not exist.
+    ///
+    /// Returns `true` if the key was set, `false` if it already held a value.
     ///
     /// # Examples
     ///
-    /// Demonstrates basic usage. This example is not **guaranteed** to always
-    /// work as it relies on time based logic and assumes the client and server
-    /// stay relatively synchronized in time. The real world tends to not be so
-    /// favorable.
+    /// Demonstrates basic usage.
     ///
     /// ```no_run
-    /// use tokio::time;
-    /// use std::time::Duration;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let set = client.set_nx("foo", "bar".into()).await.unwrap();
+    ///     println!("Set = {}", set);
+    /// }
+    /// ```
+    pub async fn set_nx(
+        &mut self,
+        key: &str,
+        value: Bytes,
+    ) -> Result<bool, MiniRedisConnectionError> {
+        let frame = SetNx::new(key, value).into_frame()?;
+
+        debug!("setnx command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(1) => Ok(true),
+            Frame::Integer(0) => Ok(false),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Sets each key to its value in a single command.
+    ///
+    /// All keys are set atomically by the server, without a time to live.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
     ///
+    /// ```no_run
     /// #[tokio::main]
     /// async fn main() {
-    ///     let ttl = Duration::from_millis(500);
     ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
     ///
-    ///     client.set_expires("foo", "bar".into(), ttl).await.unwrap();
+    ///     client
+    ///         .mset(&[("foo", "bar".into()), ("baz", "qux".into())])
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn mset(&mut self, pairs: &[(&str, Bytes)]) -> Result<(), MiniRedisConnectionError> {
+        let pairs = pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+
+        let frame = Mset::new(pairs).into_frame()?;
+
+        debug!("mset command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Simple(response) if response == "OK" => Ok(()),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Atomically sets `key` to `value`, and returns the previous value.
     ///
-    ///     // Getting the value immediately works
-    ///     let val = client.get("foo").await.unwrap().unwrap();
-    ///     assert_eq!(val, "bar");
+    /// Returns `None` if the key did not exist. Any time to live associated
+    /// with the key is discarded.
     ///
-    ///     // Wait for the TTL to expire
-    ///     time::sleep(ttl).await;
+    /// # Examples
     ///
-    ///     let val = client.get("foo").await.unwrap();
-    ///     assert!(val.is_some());
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let prev = client.getset("foo", "baz".into()).await.unwrap().unwrap();
+    ///     assert_eq!(prev, "bar");
     /// }
     /// ```
-    pub async fn set_expires(
+    pub async fn getset(
         &mut self,
         key: &str,
         value: Bytes,
-        expiration: Duration,
-    ) -> Result<(), MiniRedisConnectionError> {
-        // Create a `Set` command and pass it to `set_cmd`. A separate method is
-        // used to set a value with an expiration. The common parts of both
-        // functions are implemented by `set_cmd`.
-        self.set_cmd(Set::new(key, value, Some(expiration))).await
+    ) -> Result<Option<Bytes>, MiniRedisConnectionError> {
+        let frame = GetSet::new(key, value).into_frame()?;
+
+        debug!("getset command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Bulk(value) => Ok(Some(value)),
+            Frame::Null => Ok(None),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
     }
 
-    /// The core `SET` logic, used by both `set` and `set_expires.
-    async fn set_cmd(&mut self, cmd: Set) -> Result<(), MiniRedisConnectionError> {
-        // Convert the `Set` command into a frame
-        let frame = cmd.into_frame()?;
+    /// Returns the keys matching the glob-style `pattern`, in no particular
+    /// order.
+    ///
+    /// The pattern supports `*`, `?`, `[...]` classes and `\` escapes. The
+    /// server scans every key, so this is meant for debugging.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let keys = client.keys("user:*").await.unwrap();
+    ///     println!("Got = {:?}", keys);
+    /// }
+    /// ```
+    pub async fn keys(&mut self, pattern: &str) -> Result<Vec<String>, MiniRedisConnectionError> {
+        let frame = Keys::new(pattern).into_frame()?;
 
-        debug!("set command request: {:?}", frame);
+        debug!("keys command request: {:?}", frame);
 
-        // Write the frame to the socket. This writes the full frame to the
-        // socket, waiting if necessary.
         self.connection.write_frame(&frame).await?;
 
-        // Wait for the response from the server. On success, the server
-        // responds simply with `OK`. Any other response indicates an error.
         match self.read_response().await? {
-            Frame::Simple(response) if response == "OK" => Ok(()),
+            Frame::Array(frames) => frames
+                .into_iter()
+                .map(|frame| match frame {
+                    Frame::Bulk(key) => Ok(String::from_utf8_lossy(&key).into()),
+                    frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+                })
+                .collect(),
             frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
         }
     }
 
-    /// Posts `message` to the given `channel`.
+    /// Returns a page of the keys, along with the cursor to pass to the next
+    /// call.
     ///
-    /// Returns the number of subscribers currently listening on the channel.
-    /// There is no guarantee that these subscribers receive the message as they
-    /// may disconnect at any time.
+    /// Start with a cursor of 0, and call again until the returned cursor is
+    /// 0. A page covers about `count` keys, 10 by default, of which those
+    /// matching the glob-style `pattern` are returned, so a page may be empty
+    /// before the end. Each call walks the whole keyspace on the server. A key present during the whole iteration is returned once.
     ///
     /// # Examples
     ///
@@ -224,26 +440,780 @@ impl Client {
     /// async fn main() {
     ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
     ///
-    ///     let val = client.publish("foo", "bar".into()).await.unwrap();
-    ///     println!("Got = {:?}", val);
+    ///     let mut cursor = 0;
+    ///     loop {
+    ///         let (next, keys) = client.scan(cursor, Some("user:*"), None).await.unwrap();
+    ///         println!("Got = {:?}", keys);
+    ///         if next == 0 {
+    ///             break;
+    ///         }
+    ///         cursor = next;
+    ///     }
     /// }
     /// ```
-    pub async fn publish(
+    pub async fn scan(
         &mut self,
-        channel: &str,
-        message: Bytes,
-    ) -> Result<u64, MiniRedisConnectionError> {
-        // Convert the `Publish` command into a frame
-        let frame = Publish::new(channel, message).into_frame()?;
+        cursor: u64,
+        pattern: Option<&str>,
+        count: Option<usize>,
+    ) -> Result<(u64, Vec<String>), MiniRedisConnectionError> {
+        let frame = Scan::new(cursor, pattern, count).into_frame()?;
 
-        debug!("publish command request: {:?}", frame);
+        debug!("scan command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Array(frames) => match &frames[..] {
+                [Frame::Bulk(cursor), Frame::Array(keys)] => {
+                    let cursor = atoi::atoi(cursor).ok_or_else(|| {
+                        MiniRedisConnectionError::CommandExecute("invalid scan cursor".into())
+                    })?;
+                    let keys = keys
+                        .iter()
+                        .map(|frame| match frame {
+                            Frame::Bulk(key) => Ok(String::from_utf8_lossy(key).into()),
+                            frame => {
+                                Err(MiniRedisConnectionError::CommandExecute(frame.to_string()))
+                            }
+                        })
+                        .collect::<Result<_, _>>()?;
+                    Ok((cursor, keys))
+                }
+                _ => Err(MiniRedisConnectionError::CommandExecute(
+                    Frame::Array(frames).to_string(),
+                )),
+            },
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns the type of the value of `key`, such as `string`, or `none` if
+    /// the key does not exist.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let key_type = client.key_type("foo").await.unwrap();
+    ///     assert_eq!("string", key_type);
+    /// }
+    /// ```
+    pub async fn key_type(&mut self, key: &str) -> Result<String, MiniRedisConnectionError> {
+        let frame = Type::new(key).into_frame()?;
+
+        debug!("type command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Simple(response) => Ok(response),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns the length in bytes of the value of `key`, 0 if the key does
+    /// not exist.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let len = client.strlen("foo").await.unwrap();
+    ///     assert_eq!(3, len);
+    /// }
+    /// ```
+    pub async fn strlen(&mut self, key: &str) -> Result<u64, MiniRedisConnectionError> {
+        let frame = Strlen::new(key).into_frame()?;
+
+        debug!("strlen command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Set `key` to hold the given `value`.
+    ///
+    /// The `value` is associated with `key` until it is overwritten by the next
+    /// call to `set` or it is removed.
+    ///
+    /// If key already holds a value, it is overwritten. Any previous time to
+    /// live associated with the key is discarded on successful SET operation.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     // Getting the value immediately works
+    ///     let val = client.get("foo").await.unwrap().unwrap();
+    ///     assert_eq!(val, "bar");
+    /// }
+    /// ```
+    pub async fn set(&mut self, key: &str, value: Bytes) -> Result<(), MiniRedisConnectionError> {
+        // Create a `Set` command and pass it to `set_cmd`. A separate method is
+        // used to set a value with an expiration. The common parts of both
+        // functions are implemented by `set_cmd`.
+        self.set_cmd(Set::new(key, value, None)).await
+    }
+
+    /// Set `key` to hold the given `value`. The value expires after `expiration`
+    ///
+    /// The `value` is associated with `key` until one of the following:
+    /// - it expires.
+    /// - it is overwritten by the next call to `set`.
+    /// - it is removed.
+    ///
+    /// If key already holds a value, it is overwritten. Any previous time to
+    /// live associated with the key is discarded on a successful SET operation.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage. This example is not **guaranteed** to always
+    /// work as it relies on time based logic and assumes the client and server
+    /// stay relatively synchronized in time. The real world tends to not be so
+    /// favorable.
+    ///
+    /// ```no_run
+    /// use tokio::time;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let ttl = Duration::from_millis(500);
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set_expires("foo", "bar".into(), ttl).await.unwrap();
+    ///
+    ///     // Getting the value immediately works
+    ///     let val = client.get("foo").await.unwrap().unwrap();
+    ///     assert_eq!(val, "bar");
+    ///
+    ///     // Wait for the TTL to expire
+    ///     time::sleep(ttl).await;
+    ///
+    ///     let val = client.get("foo").await.unwrap();
+    ///     assert!(val.is_some());
+    /// }
+    /// ```
+    pub async fn set_expires(
+        &mut self,
+        key: &str,
+        value: Bytes,
+        expiration: Duration,
+    ) -> Result<(), MiniRedisConnectionError> {
+        // Create a `Set` command and pass it to `set_cmd`. A separate method is
+        // used to set a value with an expiration. The common parts of both
+        // functions are implemented by `set_cmd`.
+        self.set_cmd(Set::new(key, value, Some(expiration))).await
+    }
+
+    /// The core `SET` logic, used by both `set` and `set_expires.
+    async fn set_cmd(&mut self, cmd: Set) -> Result<(), MiniRedisConnectionError> {
+        // Convert the `Set` command into a frame
+        let frame = cmd.into_frame()?;
+
+        debug!("set command request: {:?}", frame);
+
+        // Write the frame to the socket. This writes the full frame to the
+        // socket, waiting if necessary.
+        self.connection.write_frame(&frame).await?;
+
+        // Wait for the response from the server. On success, the server
+        // responds simply with `OK`. Any other response indicates an error.
+        match self.read_response().await? {
+            Frame::Simple(response) if response == "OK" => Ok(()),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Sets the time to live of `key`, replacing any previous one.
+    ///
+    /// Returns `false` if the key does not exist. The time to live is sent
+    /// with millisecond precision, and a zero `ttl` deletes the key.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let set = client.expire("foo", Duration::from_secs(10)).await.unwrap();
+    ///     assert!(set);
+    /// }
+    /// ```
+    pub async fn expire(
+        &mut self,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<bool, MiniRedisConnectionError> {
+        let frame = PExpire::new(key, ttl).into_frame()?;
+
+        debug!("expire command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(1) => Ok(true),
+            Frame::Integer(0) => Ok(false),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns the remaining time to live of `key`, in seconds.
+    ///
+    /// As with Redis, returns -2 if the key does not exist, and -1 if the key
+    /// has no time to live.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let ttl = client.ttl("foo").await.unwrap();
+    ///     println!("Got = {}", ttl);
+    /// }
+    /// ```
+    pub async fn ttl(&mut self, key: &str) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(Ttl::new(key).into_frame()?).await
+    }
+
+    /// Returns the remaining time to live of `key`, in milliseconds.
+    ///
+    /// See [`ttl`](Client::ttl) for the special values.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let pttl = client.pttl("foo").await.unwrap();
+    ///     println!("Got = {}", pttl);
+    /// }
+    /// ```
+    pub async fn pttl(&mut self, key: &str) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(Pttl::new(key).into_frame()?).await
+    }
+
+    /// Removes the time to live of `key`, making it persistent.
+    ///
+    /// Returns `false` if the key does not exist or has no time to live.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let removed = client.persist("foo").await.unwrap();
+    ///     println!("Removed = {}", removed);
+    /// }
+    /// ```
+    pub async fn persist(&mut self, key: &str) -> Result<bool, MiniRedisConnectionError> {
+        let frame = Persist::new(key).into_frame()?;
+
+        debug!("persist command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(1) => Ok(true),
+            Frame::Integer(0) => Ok(false),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Appends `value` at the end of the value of `key`, and returns the new
+    /// length of the value.
+    ///
+    /// If the key does not exist, it is created holding `value`. The time to
+    /// live of an existing key is kept.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let len = client.append("foo", "baz".into()).await.unwrap();
+    ///     assert_eq!(6, len);
+    /// }
+    /// ```
+    pub async fn append(
+        &mut self,
+        key: &str,
+        value: Bytes,
+    ) -> Result<u64, MiniRedisConnectionError> {
+        let frame = Append::new(key, value).into_frame()?;
+
+        debug!("append command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Increments the integer value of `key` by one, and returns the new value.
+    ///
+    /// If the key does not exist, it is set to 0 first. The server replies
+    /// with an error if the value is not an integer or the result overflows.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let value = client.incr("counter").await.unwrap();
+    ///     println!("Got = {}", value);
+    /// }
+    /// ```
+    pub async fn incr(&mut self, key: &str) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(Incr::new(key).into_frame()?).await
+    }
+
+    /// Decrements the integer value of `key` by one, and returns the new value.
+    ///
+    /// See [`incr`](Client::incr) for missing keys and errors.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let value = client.decr("counter").await.unwrap();
+    ///     println!("Got = {}", value);
+    /// }
+    /// ```
+    pub async fn decr(&mut self, key: &str) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(Decr::new(key).into_frame()?).await
+    }
+
+    /// Adds `increment` to the integer value of `key`, and returns the new
+    /// value.
+    ///
+    /// See [`incr`](Client::incr) for missing keys and errors.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let value = client.incr_by("counter", 10).await.unwrap();
+    ///     println!("Got = {}", value);
+    /// }
+    /// ```
+    pub async fn incr_by(
+        &mut self,
+        key: &str,
+        increment: i64,
+    ) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(IncrBy::new(key, increment).into_frame()?)
+            .await
+    }
+
+    /// Subtracts `decrement` from the integer value of `key`, and returns the
+    /// new value.
+    ///
+    /// See [`incr`](Client::incr) for missing keys and errors.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let value = client.decr_by("counter", 10).await.unwrap();
+    ///     println!("Got = {}", value);
+    /// }
+    /// ```
+    pub async fn decr_by(
+        &mut self,
+        key: &str,
+        decrement: i64,
+    ) -> Result<i64, MiniRedisConnectionError> {
+        self.integer_cmd(DecrBy::new(key, decrement).into_frame()?)
+            .await
+    }
+
+    /// The core logic of the commands replying with an integer, such as the
+    /// counter commands.
+    async fn integer_cmd(&mut self, frame: Frame) -> Result<i64, MiniRedisConnectionError> {
+        debug!("integer command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(value) => Ok(value),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Removes the given keys.
+    ///
+    /// Returns the number of keys that were removed. Keys that do not exist are
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let removed = client.del(&["foo", "baz"]).await.unwrap();
+    ///     assert_eq!(1, removed);
+    /// }
+    /// ```
+    pub async fn del(&mut self, keys: &[&str]) -> Result<u64, MiniRedisConnectionError> {
+        let frame = Del::new(keys).into_frame()?;
+
+        debug!("del command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns how many of the given keys exist.
+    ///
+    /// A key given several times is counted as many times as it is given.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.set("foo", "bar".into()).await.unwrap();
+    ///
+    ///     let count = client.exists(&["foo", "foo", "baz"]).await.unwrap();
+    ///     assert_eq!(2, count);
+    /// }
+    /// ```
+    pub async fn exists(&mut self, keys: &[&str]) -> Result<u64, MiniRedisConnectionError> {
+        let frame = Exists::new(keys).into_frame()?;
+
+        debug!("exists command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns the number of keys in the database.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let size = client.db_size().await.unwrap();
+    ///     println!("Got = {}", size);
+    /// }
+    /// ```
+    pub async fn db_size(&mut self) -> Result<u64, MiniRedisConnectionError> {
+        let frame = DbSize::new().into_frame()?;
+
+        debug!("dbsize command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Removes all the keys of the database.
+    ///
+    /// Subscriptions are not affected.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     client.flush_db().await.unwrap();
+    /// }
+    /// ```
+    pub async fn flush_db(&mut self) -> Result<(), MiniRedisConnectionError> {
+        let frame = FlushDb::new().into_frame()?;
+
+        debug!("flushdb command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Simple(response) if response == "OK" => Ok(()),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Posts `message` to the given `channel`.
+    ///
+    /// Returns the number of subscribers currently listening on the channel.
+    /// There is no guarantee that these subscribers receive the message as they
+    /// may disconnect at any time.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let val = client.publish("foo", "bar".into()).await.unwrap();
+    ///     println!("Got = {:?}", val);
+    /// }
+    /// ```
+    pub async fn publish(
+        &mut self,
+        channel: &str,
+        message: Bytes,
+    ) -> Result<u64, MiniRedisConnectionError> {
+        // Convert the `Publish` command into a frame
+        let frame = Publish::new(channel, message).into_frame()?;
+
+        debug!("publish command request: {:?}", frame);
+
+        // Write the frame to the socket
+        self.connection.write_frame(&frame).await?;
+
+        // Read the response
+        match self.read_response().await? {
+            Frame::Integer(response) if response >= 0 => Ok(response as u64),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Posts each message to its channel in a single command.
+    ///
+    /// Returns the number of subscribers currently listening on each channel,
+    /// in the order the messages were given. All messages are published
+    /// atomically by the server.
+    ///
+    /// This uses the mini-redis specific `MPUBLISH` command.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let val = client
+    ///         .mpublish(&[("foo", "bar".into()), ("baz", "qux".into())])
+    ///         .await
+    ///         .unwrap();
+    ///     println!("Got = {:?}", val);
+    /// }
+    /// ```
+    pub async fn mpublish(
+        &mut self,
+        messages: &[(&str, Bytes)],
+    ) -> Result<Vec<u64>, MiniRedisConnectionError> {
+        let messages = messages
+            .iter()
+            .map(|(channel, message)| (channel.to_string(), message.clone()))
+            .collect();
+
+        // Convert the `MPublish` command into a frame
+        let frame = MPublish::new(messages).into_frame()?;
+
+        debug!("mpublish command request: {:?}", frame);
+
+        // Write the frame to the socket
+        self.connection.write_frame(&frame).await?;
+
+        // Read the response, one subscriber count per channel.
+        match self.read_response().await? {
+            Frame::Array(frames) => frames
+                .into_iter()
+                .map(|frame| match frame {
+                    Frame::Integer(response) if response >= 0 => Ok(response as u64),
+                    frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+                })
+                .collect(),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns information about the clients connected to the server.
+    ///
+    /// There is one line per client, made of space-separated `field=value`
+    /// pairs such as `id=1 addr=127.0.0.1:50000 tot-net-in=42 ...`.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let clients = client.client_list().await.unwrap();
+    ///     println!("{}", clients);
+    /// }
+    /// ```
+    pub async fn client_list(&mut self) -> Result<String, MiniRedisConnectionError> {
+        let frame = ClientList::new().into_frame()?;
+
+        debug!("client list command request: {:?}", frame);
+
+        self.connection.write_frame(&frame).await?;
+
+        match self.read_response().await? {
+            Frame::Bulk(value) => Ok(String::from_utf8_lossy(&value).into()),
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
+    /// Returns the `CLUSTER INFO` report of the server.
+    ///
+    /// mini-redis does not support clustering: the report always contains
+    /// `cluster_enabled:0`.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let info = client.cluster_info().await.unwrap();
+    ///     assert!(info.contains("cluster_enabled:0"));
+    /// }
+    /// ```
+    pub async fn cluster_info(&mut self) -> Result<String, MiniRedisConnectionError> {
+        self.cluster_cmd(Cluster::Info).await
+    }
+
+    /// Returns the node id of the server.
+    ///
+    /// The id is made of 40 hex characters, and is stable for the lifetime of
+    /// the server.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///
+    ///     let id = client.cluster_myid().await.unwrap();
+    ///     assert_eq!(40, id.len());
+    /// }
+    /// ```
+    pub async fn cluster_myid(&mut self) -> Result<String, MiniRedisConnectionError> {
+        self.cluster_cmd(Cluster::MyId).await
+    }
+
+    /// The core `CLUSTER` logic, used by the subcommands replying with a bulk
+    /// string.
+    async fn cluster_cmd(&mut self, cmd: Cluster) -> Result<String, MiniRedisConnectionError> {
+        let frame = cmd.into_frame()?;
+
+        debug!("cluster command request: {:?}", frame);
 
-        // Write the frame to the socket
         self.connection.write_frame(&frame).await?;
 
-        // Read the response
         match self.read_response().await? {
-            Frame::Integer(response) => Ok(response),
+            Frame::Bulk(value) => Ok(String::from_utf8_lossy(&value).into()),
             frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
         }
     }
@@ -303,7 +1273,7 @@ impl Client {
                     // num-subscribed is the number of channels that the client
                     // is currently subscribed to.
                     [subscribe, schannel, ..]
-                        if *subscribe == "subscribe" && *schannel == channel =>
+                        if *subscribe == "subscribe" && *schannel == channel.as_str() =>
                     {
                         debug!("subscribe channel: {} success", channel);
                     }
@@ -329,7 +1299,8 @@ impl Client {
 
     /// Reads a response frame from the socket.
     ///
-    /// If an `Error` frame is received, it is converted to `Err`.
+    /// If an `Error` frame is received, it is converted to
+    /// `MiniRedisConnectionError::ErrorReply`.
     pub(crate) async fn read_response(&mut self) -> Result<Frame, MiniRedisConnectionError> {
         let response = self.connection.read_frame().await?;
 
@@ -337,7 +1308,7 @@ impl Client {
 
         match response {
             // Error frames are converted to `Err`
-            Some(Frame::Error(msg)) => Err(MiniRedisConnectionError::CommandExecute(msg)),
+            Some(Frame::Error(msg)) => Err(MiniRedisConnectionError::ErrorReply(msg)),
             Some(frame) => Ok(frame),
             None => {
                 // Receiving `None` here indicates the server has closed the
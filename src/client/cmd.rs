@@ -10,6 +10,12 @@ pub enum Command {
         /// Message to ping
         msg: Option<String>,
     },
+    /// Echo the given message.
+    Echo {
+        /// Message to echo
+        #[clap(parse(from_str = bytes_from_str))]
+        msg: Bytes,
+    },
     /// Get the value of key.
     Get {
         /// Name of key to get
@@ -28,6 +34,8 @@ pub enum Command {
         #[clap(parse(try_from_str = duration_from_ms_str))]
         expires: Option<Duration>,
     },
+    /// Get the number of keys in the database.
+    DbSize,
     ///  Publisher to send a message to a specific channel.
     Publish {
         /// Name of channel
@@ -2,6 +2,7 @@ use tokio::net::{TcpStream, ToSocketAddrs};
 
 use crate::client::cli::Client;
 use crate::connection::connect::Connection;
+use crate::consts::{DEFAULT_READ_BUFFER_SIZE, DEFAULT_WRITE_BUFFER_SIZE};
 use crate::error::MiniRedisConnectionError;
 
 pub mod cli;
@@ -29,15 +30,80 @@ mod subscriber;
 /// ```
 ///
 pub async fn connect<T: ToSocketAddrs>(addr: T) -> Result<Client, MiniRedisConnectionError> {
-    // The `addr` argument is passed directly to `TcpStream::connect`. This
-    // performs any asynchronous DNS lookup and attempts to establish the TCP
-    // connection. An error at either step returns an error, which is then
-    // bubbled up to the caller of `mini_redis` connect.
-    let socket = TcpStream::connect(addr).await?;
+    ClientBuilder::new().connect(addr).await
+}
+
+/// Configures a [`Client`] before connecting it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[tokio::main]
+/// async fn main() {
+///     // A pub/sub client receiving tiny messages only.
+///     let client = mini_redis::client::ClientBuilder::new()
+///         .read_buffer_size(512)
+///         .write_buffer_size(512)
+///         .connect("localhost:6379")
+///         .await
+///         .unwrap();
+/// # drop(client);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    read_buffer_size: usize,
+    write_buffer_size: usize,
+}
+
+impl ClientBuilder {
+    /// Returns a builder with the default settings, those of [`connect`].
+    pub fn new() -> ClientBuilder {
+        ClientBuilder {
+            read_buffer_size: DEFAULT_READ_BUFFER_SIZE,
+            write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
+        }
+    }
+
+    /// Sets the initial size of the read buffer, which grows as needed.
+    ///
+    /// A client receiving large values can preallocate a larger buffer to
+    /// avoid growing it.
+    pub fn read_buffer_size(mut self, size: usize) -> ClientBuilder {
+        self.read_buffer_size = size;
+        self
+    }
 
-    // Initialize the connection state. This allocates read/write buffers to
-    // perform redis protocol frame parsing.
-    let connection = Connection::new(socket);
+    /// Sets the size of the write buffer. Values larger than the buffer are
+    /// written directly.
+    pub fn write_buffer_size(mut self, size: usize) -> ClientBuilder {
+        self.write_buffer_size = size;
+        self
+    }
+
+    /// Establish a connection with the Redis server located at `addr`, see
+    /// [`connect`].
+    pub async fn connect<T: ToSocketAddrs>(
+        self,
+        addr: T,
+    ) -> Result<Client, MiniRedisConnectionError> {
+        // The `addr` argument is passed directly to `TcpStream::connect`. This
+        // performs any asynchronous DNS lookup and attempts to establish the
+        // TCP connection. An error at either step returns an error, which is
+        // then bubbled up to the caller of `mini_redis` connect.
+        let socket = TcpStream::connect(addr).await?;
+
+        // Initialize the connection state. This allocates read/write buffers
+        // to perform redis protocol frame parsing.
+        let connection =
+            Connection::with_capacity(socket, self.read_buffer_size, self.write_buffer_size);
+
+        Ok(Client { connection })
+    }
+}
 
-    Ok(Client { connection })
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder::new()
+    }
 }
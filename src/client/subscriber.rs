@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use async_stream::try_stream;
 use bytes::Bytes;
 use log::{debug, error};
@@ -54,25 +56,74 @@ impl Subscriber {
         match self.client.connection.read_frame().await? {
             Some(frame) => {
                 debug!("subscribe received next message: {:?}", frame);
+                Ok(Some(Self::to_message(frame)?))
+            }
+            None => Ok(None),
+        }
+    }
 
-                match frame {
-                    Frame::Array(ref frame) => match frame.as_slice() {
-                        [message, channel, content] if *message == "message" => Ok(Some(Message {
-                            channel: channel.to_string(),
-                            content: Bytes::from(content.to_string()),
-                        })),
-                        _ => {
-                            error!("invalid message, frame: {:?}", frame);
-                            Err(MiniRedisConnectionError::InvalidFrameType)
-                        }
-                    },
-                    frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
-                }
+    /// Receive the next message published on a subscribed channel, without
+    /// waiting.
+    ///
+    /// `None` indicates no message has been received yet. This allows polling
+    /// several subscribers from a custom event loop. Unlike `next_message`, a
+    /// terminated subscription is reported as
+    /// `MiniRedisConnectionError::Disconnect`.
+    ///
+    /// # Examples
+    ///
+    /// Demonstrates basic usage.
+    ///
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = mini_redis::client::connect("localhost:6379").await.unwrap();
+    ///     let mut subscriber = client.subscribe(vec!["foo".into()]).await.unwrap();
+    ///
+    ///     match subscriber.try_next_message().unwrap() {
+    ///         Some(message) => println!("got = {:?}", message),
+    ///         None => println!("no message yet"),
+    ///     }
+    /// }
+    /// ```
+    pub fn try_next_message(&mut self) -> Result<Option<Message>, MiniRedisConnectionError> {
+        match self.client.connection.try_read_frame()? {
+            Some(frame) => {
+                debug!("subscribe received next message: {:?}", frame);
+                Ok(Some(Self::to_message(frame)?))
             }
             None => Ok(None),
         }
     }
 
+    /// Converts a frame pushed by the server into a `Message`.
+    ///
+    /// The content is taken as is from the frame, so binary messages are
+    /// received byte for byte.
+    fn to_message(frame: Frame) -> Result<Message, MiniRedisConnectionError> {
+        match frame {
+            Frame::Array(frame) => match <[Frame; 3]>::try_from(frame) {
+                Ok([message, channel, Frame::Bulk(content)]) if message == "message" => {
+                    Ok(Message {
+                        channel: channel.to_string(),
+                        content,
+                    })
+                }
+                Ok([message, channel, Frame::Simple(content)]) if message == "message" => {
+                    Ok(Message {
+                        channel: channel.to_string(),
+                        content: Bytes::from(content),
+                    })
+                }
+                frame => {
+                    error!("invalid message, frame: {:?}", frame);
+                    Err(MiniRedisConnectionError::InvalidFrameType)
+                }
+            },
+            frame => Err(MiniRedisConnectionError::CommandExecute(frame.to_string())),
+        }
+    }
+
     /// Convert the subscriber into a `Stream` yielding new messages published
     /// on subscribed channels.
     ///
@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Appends a value at the end of the string stored at key.
+///
+/// If the key does not exist, it is created holding the value, as with `SET`.
+/// The time to live of an existing key is kept.
+#[derive(Debug)]
+pub struct Append {
+    /// Name of the key to append to
+    key: String,
+
+    /// Value to append, binary safe
+    value: Bytes,
+}
+
+impl Append {
+    /// Create a new `Append` command which appends `value` to `key`.
+    pub(crate) fn new(key: impl ToString, value: Bytes) -> Append {
+        Append {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    /// Parse an `Append` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `APPEND` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Append` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// APPEND key value
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Append, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let value = parse.next_bytes()?;
+
+        Ok(Append { key, value })
+    }
+
+    /// Apply the `Append` command to the specified `Db` instance.
+    ///
+    /// The length of the value after the append is written to `dst`.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let len = db.append(self.key, self.value);
+        let response = Frame::Integer(len as i64);
+
+        debug!("append command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding an `Append` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("append".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
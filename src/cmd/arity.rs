@@ -0,0 +1,234 @@
+//! Number of arguments accepted by each command, and other command metadata
+//! reported by `COMMAND`.
+//!
+//! Arities are validated by `Command::from_frame` before the command is
+//! parsed, so that every command reports a wrong number of arguments with the
+//! same error.
+use crate::error::MiniRedisParseError;
+
+/// Number of arguments accepted by a command, not counting the command name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Arity {
+    /// Minimum number of arguments.
+    pub(crate) min: usize,
+
+    /// Maximum number of arguments, `None` if the command is variadic.
+    pub(crate) max: Option<usize>,
+}
+
+impl Arity {
+    /// A command accepting exactly `n` arguments.
+    const fn exactly(n: usize) -> Arity {
+        Arity {
+            min: n,
+            max: Some(n),
+        }
+    }
+
+    /// A command accepting between `min` and `max` arguments.
+    const fn between(min: usize, max: usize) -> Arity {
+        Arity {
+            min,
+            max: Some(max),
+        }
+    }
+
+    /// A variadic command accepting at least `min` arguments.
+    const fn at_least(min: usize) -> Arity {
+        Arity { min, max: None }
+    }
+
+    /// The arity as reported by Redis: the number of arguments counting the
+    /// command name, negated for commands accepting a variable number of
+    /// arguments, which then is the minimum.
+    pub(crate) fn redis_arity(&self) -> i64 {
+        let n = self.min as i64 + 1;
+        if self.max == Some(self.min) {
+            n
+        } else {
+            -n
+        }
+    }
+
+    /// Returns `true` if the command accepts `args` arguments.
+    pub(crate) fn accepts(&self, args: usize) -> bool {
+        args >= self.min && self.max.is_none_or(|max| args <= max)
+    }
+}
+
+/// Positions of the key arguments of a command, as reported by `COMMAND`.
+///
+/// Positions are 1-based, counting the command name as 0. All are `0` for a
+/// command taking no key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Keys {
+    pub(crate) first: i64,
+    pub(crate) last: i64,
+    pub(crate) step: i64,
+}
+
+impl Keys {
+    /// The command takes no key.
+    const NONE: Keys = Keys {
+        first: 0,
+        last: 0,
+        step: 0,
+    };
+
+    /// The first argument is the only key.
+    const FIRST: Keys = Keys {
+        first: 1,
+        last: 1,
+        step: 1,
+    };
+
+    /// Arguments are key/value pairs.
+    const PAIRS: Keys = Keys {
+        first: 1,
+        last: -1,
+        step: 2,
+    };
+
+    /// Every argument is a key.
+    const ALL: Keys = Keys {
+        first: 1,
+        last: -1,
+        step: 1,
+    };
+}
+
+/// Metadata of a supported command.
+#[derive(Debug)]
+pub(crate) struct Spec {
+    /// Lower case command name.
+    pub(crate) name: &'static str,
+
+    pub(crate) arity: Arity,
+
+    /// Redis command flags, e.g. `readonly`, `write` or `pubsub`. Clients use
+    /// them to classify and route commands.
+    pub(crate) flags: &'static [&'static str],
+
+    pub(crate) keys: Keys,
+}
+
+impl Spec {
+    const fn new(name: &'static str, arity: Arity, flags: &'static [&'static str]) -> Spec {
+        Spec {
+            name,
+            arity,
+            flags,
+            keys: Keys::NONE,
+        }
+    }
+
+    const fn with_keys(self, keys: Keys) -> Spec {
+        Spec { keys, ..self }
+    }
+}
+
+/// Metadata of each supported command.
+///
+/// For commands with subcommands, the subcommand counts as an argument.
+static COMMANDS: &[Spec] = &[
+    Spec::new("get", Arity::exactly(1), &["readonly", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("getrange", Arity::exactly(3), &["readonly"]).with_keys(Keys::FIRST),
+    Spec::new("mget", Arity::at_least(1), &["readonly", "fast"]).with_keys(Keys::ALL),
+    Spec::new("strlen", Arity::exactly(1), &["readonly", "fast"]).with_keys(Keys::FIRST),
+    // SET key value [EX seconds|PX milliseconds]
+    Spec::new("set", Arity::between(2, 4), &["write", "denyoom"]).with_keys(Keys::FIRST),
+    Spec::new("mset", Arity::at_least(2), &["write", "denyoom"]).with_keys(Keys::PAIRS),
+    Spec::new("setnx", Arity::exactly(2), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("append", Arity::exactly(2), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("getset", Arity::exactly(2), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("del", Arity::at_least(1), &["write"]).with_keys(Keys::ALL),
+    Spec::new("exists", Arity::at_least(1), &["readonly", "fast"]).with_keys(Keys::ALL),
+    Spec::new("expire", Arity::exactly(2), &["write", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("pexpire", Arity::exactly(2), &["write", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("persist", Arity::exactly(1), &["write", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("ttl", Arity::exactly(1), &["readonly", "random", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("pttl", Arity::exactly(1), &["readonly", "random", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("type", Arity::exactly(1), &["readonly", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("keys", Arity::exactly(1), &["readonly", "sortable"]),
+    // SCAN cursor [MATCH pattern] [COUNT count]
+    Spec::new("scan", Arity::between(1, 5), &["readonly", "random"]),
+    Spec::new("dbsize", Arity::exactly(0), &["readonly", "fast"]),
+    Spec::new("flushdb", Arity::exactly(0), &["write"]),
+    Spec::new("rename", Arity::exactly(2), &["write"]).with_keys(Keys::ALL),
+    Spec::new("renamenx", Arity::exactly(2), &["write", "fast"]).with_keys(Keys::ALL),
+    Spec::new("incr", Arity::exactly(1), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("decr", Arity::exactly(1), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("incrby", Arity::exactly(2), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new("decrby", Arity::exactly(2), &["write", "denyoom", "fast"]).with_keys(Keys::FIRST),
+    Spec::new(
+        "publish",
+        Arity::exactly(2),
+        &["pubsub", "loading", "stale", "fast"],
+    ),
+    Spec::new(
+        "mpublish",
+        Arity::at_least(2),
+        &["pubsub", "loading", "stale"],
+    ),
+    Spec::new(
+        "subscribe",
+        Arity::at_least(1),
+        &["pubsub", "noscript", "loading", "stale"],
+    ),
+    Spec::new(
+        "unsubscribe",
+        Arity::at_least(0),
+        &["pubsub", "noscript", "loading", "stale"],
+    ),
+    Spec::new("ping", Arity::between(0, 1), &["stale", "fast"]),
+    Spec::new("echo", Arity::exactly(1), &["fast"]),
+    Spec::new(
+        "client",
+        Arity::exactly(1),
+        &["admin", "noscript", "random", "loading", "stale"],
+    ),
+    Spec::new("cluster", Arity::exactly(1), &["admin", "random", "stale"]),
+    // DEBUG HISTORY|KEYCOUNT|PURGE|DUMPHEX key|BIGKEYS [count]
+    Spec::new(
+        "debug",
+        Arity::between(1, 2),
+        &["admin", "noscript", "loading", "stale"],
+    ),
+    // LOGTAIL [level]
+    Spec::new(
+        "logtail",
+        Arity::between(0, 1),
+        &["admin", "noscript", "loading", "stale"],
+    ),
+    // COMMAND [INFO [command ...]]
+    Spec::new(
+        "command",
+        Arity::at_least(0),
+        &["random", "loading", "stale"],
+    ),
+];
+
+/// Returns the metadata of every supported command.
+pub(crate) fn specs() -> &'static [Spec] {
+    COMMANDS
+}
+
+/// Returns the metadata of the command named `name`, if it is supported.
+pub(crate) fn spec(name: &str) -> Option<&'static Spec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+/// Returns the arity of the command named `name`, if it is supported.
+pub(crate) fn lookup(name: &str) -> Option<Arity> {
+    spec(name).map(|spec| spec.arity)
+}
+
+/// Check that the command named `name` accepts `args` arguments.
+///
+/// Unsupported commands are not checked, they are answered as unknown.
+pub(crate) fn check(name: &str, args: usize) -> Result<(), MiniRedisParseError> {
+    match lookup(name) {
+        Some(arity) if !arity.accepts(args) => Err(MiniRedisParseError::WrongArity(name.into())),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Write;
+
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+
+/// Returns information about the connected clients.
+///
+/// The reply is a bulk string with one line per client, made of
+/// space-separated `field=value` pairs:
+///
+/// * `id` -- unique connection id.
+/// * `addr` -- address of the client.
+/// * `tot-net-in` -- total bytes read from the client.
+/// * `tot-net-out` -- total bytes written to the client.
+/// * `tot-frames` -- total frames parsed from the client.
+/// * `tot-cmds` -- total commands applied for the client.
+#[derive(Debug, Default)]
+pub struct ClientList;
+
+impl ClientList {
+    /// Create a new `ClientList` command.
+    pub(crate) fn new() -> ClientList {
+        ClientList
+    }
+
+    /// Parse a `ClientList` instance from a received frame.
+    ///
+    /// The `CLIENT LIST` strings have already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing exactly two entries.
+    ///
+    /// ```text
+    /// CLIENT LIST
+    /// ```
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> Result<ClientList, MiniRedisParseError> {
+        Ok(ClientList)
+    }
+
+    /// Apply the `ClientList` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let mut list = String::new();
+        for (id, info) in db.clients() {
+            // Writing to a `String` cannot fail.
+            let _ = writeln!(
+                list,
+                "id={} addr={} tot-net-in={} tot-net-out={} tot-frames={} tot-cmds={}",
+                id,
+                info.addr,
+                info.stats.bytes_read(),
+                info.stats.bytes_written(),
+                info.stats.frames_read(),
+                info.stats.commands_applied(),
+            );
+        }
+
+        let response = Frame::Bulk(Bytes::from(list));
+        debug!("apply client list command response: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `ClientList` command to
+    /// send to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("client".as_bytes()))?;
+        frame.push_bulk(Bytes::from("list".as_bytes()))?;
+        Ok(frame)
+    }
+}
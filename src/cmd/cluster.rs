@@ -0,0 +1,117 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+
+/// Reply to `CLUSTER INFO`, reporting a standalone node.
+const CLUSTER_INFO: &str = "cluster_enabled:0\r\n\
+                            cluster_state:ok\r\n\
+                            cluster_slots_assigned:0\r\n\
+                            cluster_slots_ok:0\r\n\
+                            cluster_slots_pfail:0\r\n\
+                            cluster_slots_fail:0\r\n\
+                            cluster_known_nodes:1\r\n\
+                            cluster_size:0\r\n\
+                            cluster_current_epoch:0\r\n\
+                            cluster_my_epoch:0\r\n";
+
+/// Compatibility stubs for the `CLUSTER` introspection subcommands.
+///
+/// mini-redis does not implement clustering. Cluster-aware client libraries
+/// probe these subcommands on connect even against a single node, so they are
+/// answered as a standalone node would: clustering is reported as disabled,
+/// and no slots or shards are served.
+#[derive(Debug)]
+pub enum Cluster {
+    /// `CLUSTER INFO`: reports `cluster_enabled:0`.
+    Info,
+
+    /// `CLUSTER MYID`: the 40 characters id of this node, stable for the
+    /// server's lifetime.
+    MyId,
+
+    /// `CLUSTER SLOTS`: always an empty array.
+    Slots,
+
+    /// `CLUSTER SHARDS`: always an empty array.
+    Shards,
+}
+
+impl Cluster {
+    /// Parse a `Cluster` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `CLUSTER` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `Cluster` value is returned. If the frame is malformed
+    /// or the subcommand is not supported, `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// CLUSTER INFO|MYID|SLOTS|SHARDS
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Cluster, MiniRedisParseError> {
+        match &parse.next_string()?.to_lowercase()[..] {
+            "info" => Ok(Cluster::Info),
+            "myid" => Ok(Cluster::MyId),
+            "slots" => Ok(Cluster::Slots),
+            "shards" => Ok(Cluster::Shards),
+            subcommand => Err(MiniRedisParseError::Parse(format!(
+                "unknown subcommand '{}'",
+                subcommand
+            ))),
+        }
+    }
+
+    /// Apply the `Cluster` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = match self {
+            Cluster::Info => Frame::Bulk(Bytes::from_static(CLUSTER_INFO.as_bytes())),
+            Cluster::MyId => Frame::Bulk(Bytes::from(db.node_id().to_string())),
+            Cluster::Slots | Cluster::Shards => Frame::array(),
+        };
+
+        debug!("apply cluster command response: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Cluster` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let subcommand = match self {
+            Cluster::Info => "info",
+            Cluster::MyId => "myid",
+            Cluster::Slots => "slots",
+            Cluster::Shards => "shards",
+        };
+
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("cluster".as_bytes()))?;
+        frame.push_bulk(Bytes::from(subcommand.as_bytes()))?;
+        Ok(frame)
+    }
+}
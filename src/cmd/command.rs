@@ -0,0 +1,112 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::cmd::arity::{self, Spec};
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+
+/// Introspection of the commands supported by the server.
+///
+/// Client libraries use it to learn the arity, flags and key positions of each
+/// command, e.g. to tell read-only commands from writes.
+#[derive(Debug)]
+pub struct CommandInfo {
+    /// The commands to describe, `None` for all supported commands.
+    names: Option<Vec<String>>,
+}
+
+impl CommandInfo {
+    /// Parse a `CommandInfo` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `COMMAND` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `CommandInfo` value is returned. If the frame is
+    /// malformed or the subcommand is not supported, `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing one or more entries.
+    ///
+    /// ```text
+    /// COMMAND [INFO [command ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<CommandInfo, MiniRedisParseError> {
+        match parse.next_string() {
+            Ok(subcommand) if subcommand.eq_ignore_ascii_case("info") => {}
+            Ok(subcommand) => {
+                return Err(MiniRedisParseError::Parse(format!(
+                    "unknown subcommand '{}'",
+                    subcommand.to_lowercase()
+                )))
+            }
+            Err(MiniRedisParseError::EndOfStream) => return Ok(CommandInfo { names: None }),
+            Err(err) => return Err(err),
+        }
+
+        let mut names = vec![];
+        loop {
+            match parse.next_string() {
+                Ok(name) => names.push(name),
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        // `COMMAND INFO` without names describes all commands, as `COMMAND`.
+        if names.is_empty() {
+            return Ok(CommandInfo { names: None });
+        }
+        Ok(CommandInfo { names: Some(names) })
+    }
+
+    /// Apply the `CommandInfo` command.
+    ///
+    /// The response is an array with one entry per command. Each entry is the
+    /// 6 elements Redis reply: name, arity, flags, first key, last key and key
+    /// step. Unknown commands are described by a null.
+    pub(crate) async fn apply(self, dst: &mut Connection) -> Result<(), MiniRedisConnectionError> {
+        let response = match self.names {
+            Some(names) => Frame::Array(
+                names
+                    .iter()
+                    .map(|name| match arity::spec(&name.to_lowercase()) {
+                        Some(spec) => describe(spec),
+                        None => Frame::Null,
+                    })
+                    .collect(),
+            ),
+            None => Frame::Array(arity::specs().iter().map(describe).collect()),
+        };
+
+        debug!("apply command command response: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+}
+
+/// Creates the `COMMAND` reply describing a command.
+fn describe(spec: &Spec) -> Frame {
+    Frame::Array(vec![
+        Frame::Bulk(Bytes::from_static(spec.name.as_bytes())),
+        Frame::Integer(spec.arity.redis_arity()),
+        Frame::Array(
+            spec.flags
+                .iter()
+                .map(|flag| Frame::Simple(flag.to_string()))
+                .collect(),
+        ),
+        Frame::Integer(spec.keys.first),
+        Frame::Integer(spec.keys.last),
+        Frame::Integer(spec.keys.step),
+    ])
+}
@@ -0,0 +1,65 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Returns the number of keys in the database.
+///
+/// Keys which expired but were not purged yet are not counted.
+#[derive(Debug, Default)]
+pub struct DbSize;
+
+impl DbSize {
+    /// Create a new `DbSize` command.
+    pub(crate) fn new() -> DbSize {
+        DbSize
+    }
+
+    /// Parse a `DbSize` instance from a received frame.
+    ///
+    /// The `DBSIZE` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing exactly one entry.
+    ///
+    /// ```text
+    /// DBSIZE
+    /// ```
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> Result<DbSize, MiniRedisParseError> {
+        Ok(DbSize)
+    }
+
+    /// Apply the `DbSize` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Integer(db.db_size() as i64);
+
+        debug!("dbsize command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `DbSize` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("dbsize".as_bytes()))?;
+        Ok(frame)
+    }
+}
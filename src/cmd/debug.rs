@@ -0,0 +1,181 @@
+use std::convert::TryFrom;
+
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+
+/// Introspection subcommands of `DEBUG`, exposing server internals.
+///
+/// They are only available when `ServerConfig::debug_commands` is set.
+#[derive(Debug)]
+pub enum DebugCommand {
+    /// `DEBUG HISTORY`: returns the last commands received by the server.
+    ///
+    /// Commands are only recorded when `ServerConfig::command_history` is
+    /// set. The reply is an array with one bulk string per command, oldest
+    /// first, formatted as `<unix time in ms> <client address> <command and
+    /// arguments>`. Long arguments are truncated.
+    History,
+
+    /// `DEBUG KEYCOUNT`: returns an array of two integers, the number of keys
+    /// in the store and the number of those which have not expired.
+    ///
+    /// Expired keys stay in the store until they are purged, so the first
+    /// count may be greater. This allows white-box testing of the expiry
+    /// machinery.
+    KeyCount,
+
+    /// `DEBUG PURGE`: purges the expired keys right away, and returns the
+    /// number of keys purged.
+    ///
+    /// This allows reclaiming memory, or testing expiration, without waiting
+    /// for the purge task.
+    Purge,
+
+    /// `DEBUG DUMPHEX key`: returns the value of `key` as stored, hex
+    /// encoded, or a null if the key is missing.
+    ///
+    /// This allows inspecting binary values safely from a terminal. Values
+    /// longer than `ServerConfig::debug_dump_limit` are truncated, and the
+    /// dump then ends with `...`.
+    DumpHex(String),
+
+    /// `DEBUG BIGKEYS [count]`: returns the `count` largest keys, 10 by
+    /// default, largest first. Each key is reported as an array of the key
+    /// and its estimated memory in bytes, i.e. the length of the key and of
+    /// its value.
+    ///
+    /// All the keys are first cloned under a single read lock, then sized in
+    /// batches, releasing the store lock in between so that writes are not
+    /// stalled for the whole sizing. Counts past the number of keys report
+    /// all the keys.
+    BigKeys(usize),
+}
+
+/// Number of keys reported by `DEBUG BIGKEYS` without a count.
+const DEFAULT_BIGKEYS_COUNT: usize = 10;
+
+impl DebugCommand {
+    /// Parse a `DebugCommand` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `DEBUG` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `DebugCommand` value is returned. If the frame is
+    /// malformed or the subcommand is not supported, `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two or three entries.
+    ///
+    /// ```text
+    /// DEBUG HISTORY|KEYCOUNT|PURGE
+    /// DEBUG DUMPHEX key
+    /// DEBUG BIGKEYS [count]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<DebugCommand, MiniRedisParseError> {
+        match &parse.next_string()?.to_lowercase()[..] {
+            "history" => Ok(DebugCommand::History),
+            "keycount" => Ok(DebugCommand::KeyCount),
+            "purge" => Ok(DebugCommand::Purge),
+            "dumphex" => Ok(DebugCommand::DumpHex(parse.next_string()?)),
+            "bigkeys" => match parse.next_int() {
+                Ok(count) => Ok(DebugCommand::BigKeys(
+                    usize::try_from(count).unwrap_or(usize::MAX),
+                )),
+                Err(MiniRedisParseError::EndOfStream) => {
+                    Ok(DebugCommand::BigKeys(DEFAULT_BIGKEYS_COUNT))
+                }
+                Err(err) => Err(err),
+            },
+            subcommand => Err(MiniRedisParseError::Parse(format!(
+                "unknown subcommand '{}'",
+                subcommand
+            ))),
+        }
+    }
+
+    /// Apply the `DebugCommand` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        if !db.config().debug_commands {
+            let response = Frame::Error("ERR DEBUG command not allowed".into());
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+
+        let response = match self {
+            DebugCommand::History => {
+                let mut response = Frame::array();
+                for entry in db.history() {
+                    let line = format!("{} {} {}", entry.unix_ms, entry.addr, entry.command);
+                    response.push_bulk(Bytes::from(line))?;
+                }
+                response
+            }
+            DebugCommand::KeyCount => {
+                let (raw, live) = db.key_counts();
+                let mut response = Frame::array();
+                response.push_int(raw as i64)?;
+                response.push_int(live as i64)?;
+                response
+            }
+            DebugCommand::Purge => Frame::Integer(db.purge_expired() as i64),
+            DebugCommand::DumpHex(key) => match db.raw_value(&key) {
+                Some(value) => {
+                    Frame::Bulk(Bytes::from(dump_hex(&value, db.config().debug_dump_limit)))
+                }
+                None => Frame::Null,
+            },
+            DebugCommand::BigKeys(count) => Frame::Array(
+                db.biggest_keys(count)
+                    .into_iter()
+                    .map(|(key, memory)| {
+                        Frame::Array(vec![
+                            Frame::Bulk(Bytes::from(key)),
+                            Frame::Integer(memory as i64),
+                        ])
+                    })
+                    .collect(),
+            ),
+        };
+
+        debug!("apply debug command response: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+}
+
+/// Hex encodes at most `limit` bytes of `value`, ending with `...` if it is
+/// truncated.
+fn dump_hex(value: &[u8], limit: Option<usize>) -> String {
+    use std::fmt::Write;
+
+    let len = limit.map_or(value.len(), |limit| limit.min(value.len()));
+    let mut dump = String::with_capacity(2 * len + 3);
+    for byte in &value[..len] {
+        let _ = write!(dump, "{:02x}", byte);
+    }
+    if len < value.len() {
+        dump.push_str("...");
+    }
+    dump
+}
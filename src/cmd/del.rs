@@ -0,0 +1,96 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Removes the specified keys.
+///
+/// A key is ignored if it does not exist. The number of keys that were removed
+/// is returned.
+#[derive(Debug)]
+pub struct Del {
+    /// Names of the keys to remove
+    keys: Vec<String>,
+}
+
+impl Del {
+    /// Create a new `Del` command which removes `keys`.
+    pub(crate) fn new(keys: &[&str]) -> Del {
+        Del {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    /// Parse a `Del` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `DEL` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Del` value on success. If the frame is malformed, `Err` is
+    /// returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two or more entries.
+    ///
+    /// ```text
+    /// DEL key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Del, MiniRedisParseError> {
+        // At least one key is required.
+        let mut keys = vec![parse.next_string()?];
+
+        loop {
+            match parse.next_string() {
+                Ok(key) => keys.push(key),
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Del { keys })
+    }
+
+    /// Apply the `Del` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Integer(db.del(&self.keys) as i64);
+
+        debug!("del command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Del` command to send to
+    /// the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("del".as_bytes()))?;
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+        Ok(frame)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use bytes::Bytes;
+
+/// Returns a copy of the argument as a bulk.
+///
+/// Unlike `PING`, the message is mandatory and always echoed. This is mostly
+/// useful for protocol-level testing and to measure latency.
+#[derive(Debug)]
+pub struct Echo {
+    /// message to be returned
+    msg: Bytes,
+}
+
+impl Echo {
+    /// Create a new `Echo` command returning `msg`.
+    pub(crate) fn new(msg: Bytes) -> Echo {
+        Echo { msg }
+    }
+
+    /// Parse an `Echo` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `ECHO` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Echo` value on success. If the frame is malformed, `Err` is
+    /// returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing `ECHO` and a message.
+    ///
+    /// ```text
+    /// ECHO message
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Echo, MiniRedisParseError> {
+        let msg = parse.next_bytes()?;
+
+        Ok(Echo { msg })
+    }
+
+    /// Apply the `Echo` command and return the message.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(self, dst: &mut Connection) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Bulk(self.msg);
+
+        // Write the response back to the client
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding an `Echo` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("echo".as_bytes()))?;
+        frame.push_bulk(self.msg)?;
+        Ok(frame)
+    }
+}
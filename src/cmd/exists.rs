@@ -0,0 +1,96 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Counts how many of the specified keys exist.
+///
+/// A key given several times is counted as many times as it is given.
+#[derive(Debug)]
+pub struct Exists {
+    /// Names of the keys to check
+    keys: Vec<String>,
+}
+
+impl Exists {
+    /// Create a new `Exists` command which checks `keys`.
+    pub(crate) fn new(keys: &[&str]) -> Exists {
+        Exists {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    /// Parse an `Exists` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `EXISTS` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Exists` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two or more entries.
+    ///
+    /// ```text
+    /// EXISTS key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Exists, MiniRedisParseError> {
+        // At least one key is required.
+        let mut keys = vec![parse.next_string()?];
+
+        loop {
+            match parse.next_string() {
+                Ok(key) => keys.push(key),
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Exists { keys })
+    }
+
+    /// Apply the `Exists` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let count = self.keys.iter().filter(|key| db.exists(key)).count();
+        let response = Frame::Integer(count as i64);
+
+        debug!("exists command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding an `Exists` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("exists".as_bytes()))?;
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+        Ok(frame)
+    }
+}
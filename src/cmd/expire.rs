@@ -0,0 +1,161 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Set a timeout on key, in seconds.
+///
+/// After the timeout has expired, the key is deleted. Any previous time to live
+/// associated with the key is replaced. Replies 1 if the timeout was set, 0 if
+/// the key does not exist.
+///
+/// As with Redis, a timeout which is not positive deletes the key right away,
+/// and replies 1 if the key existed.
+#[derive(Debug)]
+pub struct Expire {
+    /// Name of the key
+    key: String,
+
+    /// Time to live, in seconds
+    seconds: i64,
+}
+
+/// Set a timeout on key, in milliseconds.
+///
+/// See `Expire`.
+#[derive(Debug)]
+pub struct PExpire {
+    /// Name of the key
+    key: String,
+
+    /// Time to live, in milliseconds
+    milliseconds: i64,
+}
+
+impl Expire {
+    /// Parse an `Expire` instance from a received frame.
+    ///
+    /// The `EXPIRE` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// EXPIRE key seconds
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Expire, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let seconds = parse.next_signed_int()?;
+
+        Ok(Expire { key, seconds })
+    }
+
+    /// Apply the `Expire` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let ttl = positive(self.seconds).map(Duration::from_secs);
+        apply_ttl(db, dst, "expire", &self.key, ttl).await
+    }
+}
+
+impl PExpire {
+    /// Create a new `PExpire` command which sets the time to live of `key`.
+    ///
+    /// The time to live is sent in milliseconds, as a `Duration` may be more
+    /// precise. Longer times to live than the protocol allows are capped.
+    pub(crate) fn new(key: impl ToString, ttl: Duration) -> PExpire {
+        PExpire {
+            key: key.to_string(),
+            milliseconds: i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX),
+        }
+    }
+
+    /// Parse a `PExpire` instance from a received frame.
+    ///
+    /// The `PEXPIRE` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// PEXPIRE key milliseconds
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<PExpire, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let milliseconds = parse.next_signed_int()?;
+
+        Ok(PExpire { key, milliseconds })
+    }
+
+    /// Apply the `PExpire` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let ttl = positive(self.milliseconds).map(Duration::from_millis);
+        apply_ttl(db, dst, "pexpire", &self.key, ttl).await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `PExpire` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("pexpire".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_int(self.milliseconds)?;
+        Ok(frame)
+    }
+}
+
+/// Returns `value` as unsigned if it is positive.
+fn positive(value: i64) -> Option<u64> {
+    u64::try_from(value).ok().filter(|&value| value > 0)
+}
+
+/// Sets the time to live of `key`, and writes whether the key exists to `dst`.
+///
+/// A `None` time to live, i.e. not positive, deletes the key. `name` is the
+/// command reported when the expiration instant would overflow.
+async fn apply_ttl(
+    db: &Db,
+    dst: &mut Connection,
+    name: &str,
+    key: &str,
+    ttl: Option<Duration>,
+) -> Result<(), MiniRedisConnectionError> {
+    let response = match ttl {
+        Some(ttl) => match db.expire(key, ttl) {
+            Some(set) => Frame::Integer(set as i64),
+            None => Frame::Error(format!("ERR invalid expire time in '{}' command", name)),
+        },
+        None => Frame::Integer((db.del(&[key.to_string()]) > 0) as i64),
+    };
+
+    debug!("expire command applied resp: {:?}", response);
+
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Removes all the keys of the database.
+///
+/// As with Redis, the pub/sub channels and their subscribers are kept.
+#[derive(Debug, Default)]
+pub struct FlushDb;
+
+impl FlushDb {
+    /// Create a new `FlushDb` command.
+    pub(crate) fn new() -> FlushDb {
+        FlushDb
+    }
+
+    /// Parse a `FlushDb` instance from a received frame.
+    ///
+    /// The `FLUSHDB` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing exactly one entry.
+    ///
+    /// ```text
+    /// FLUSHDB
+    /// ```
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> Result<FlushDb, MiniRedisParseError> {
+        Ok(FlushDb)
+    }
+
+    /// Apply the `FlushDb` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        db.flush();
+        let response = Frame::Simple("OK".to_string());
+
+        debug!("flushdb command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `FlushDb` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("flushdb".as_bytes()))?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,96 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Get a substring of the value of key.
+///
+/// `start` and `end` are inclusive byte offsets. Negative offsets count from
+/// the end of the value, `-1` being the last byte. Offsets out of the value
+/// are clamped to it rather than rejected. An empty bulk is returned if the
+/// range is empty, or if the key does not exist.
+#[derive(Debug)]
+pub struct GetRange {
+    /// Name of the key to get
+    key: String,
+
+    /// Offset of the first byte of the range.
+    start: i64,
+
+    /// Offset of the last byte of the range.
+    end: i64,
+}
+
+impl GetRange {
+    /// Parse a `GetRange` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `GETRANGE` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `GetRange` value on success. If the frame is malformed,
+    /// `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing four entries.
+    ///
+    /// ```text
+    /// GETRANGE key start end
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<GetRange, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let start = parse.next_signed_int()?;
+        let end = parse.next_signed_int()?;
+
+        Ok(GetRange { key, start, end })
+    }
+
+    /// Apply the `GetRange` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let value = db.get(&self.key).unwrap_or_default();
+        let response = match range(value.len(), self.start, self.end) {
+            Some((start, end)) => Frame::Bulk(value.slice(start..=end)),
+            None => Frame::Bulk(Bytes::new()),
+        };
+
+        debug!("getrange command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+}
+
+/// Resolves the inclusive range `start..=end` of a value of `len` bytes, as
+/// Redis does. Returns `None` if the range is empty.
+fn range(len: usize, start: i64, end: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    if start < 0 && end < 0 && start > end {
+        return None;
+    }
+
+    let start = if start < 0 { len + start } else { start }.max(0);
+    let end = if end < 0 { len + end } else { end }.max(0).min(len - 1);
+    if start > end || len == 0 {
+        return None;
+    }
+
+    Some((start as usize, end as usize))
+}
@@ -0,0 +1,92 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Atomically sets `key` to `value` and returns the value previously stored at
+/// `key`.
+///
+/// If the key does not exist, the special value nil is returned. As with
+/// `SET`, any previous time to live associated with the key is discarded.
+#[derive(Debug)]
+pub struct GetSet {
+    /// Name of the key to set
+    key: String,
+
+    /// The new value
+    value: Bytes,
+}
+
+impl GetSet {
+    /// Create a new `GetSet` command which sets `key` to `value`.
+    pub(crate) fn new(key: impl ToString, value: Bytes) -> GetSet {
+        GetSet {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    /// Parse a `GetSet` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `GETSET` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `GetSet` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// GETSET key value
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<GetSet, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let value = parse.next_bytes()?;
+
+        Ok(GetSet { key, value })
+    }
+
+    /// Apply the `GetSet` command to the specified `Db` instance.
+    ///
+    /// The previous value is written to `dst`, or `Null` if there was none.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = match db.getset(self.key, self.value) {
+            Some(value) => Frame::Bulk(value),
+            None => Frame::Null,
+        };
+
+        debug!("getset command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `GetSet` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("getset".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,282 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Increments the integer value of a key by one.
+///
+/// If the key does not exist, it is set to 0 before performing the operation.
+/// An error is returned if the value is not a base-10 integer, or if the result
+/// is out of the range of a 64 bit signed integer.
+#[derive(Debug)]
+pub struct Incr {
+    /// Name of the key to increment
+    key: String,
+}
+
+/// Decrements the integer value of a key by one.
+///
+/// See `Incr` for the handling of missing keys and invalid values.
+#[derive(Debug)]
+pub struct Decr {
+    /// Name of the key to decrement
+    key: String,
+}
+
+/// Increments the integer value of a key by the given amount, which may be
+/// negative.
+///
+/// See `Incr` for the handling of missing keys and invalid values.
+#[derive(Debug)]
+pub struct IncrBy {
+    /// Name of the key to increment
+    key: String,
+
+    /// Amount added to the value
+    increment: i64,
+}
+
+/// Decrements the integer value of a key by the given amount, which may be
+/// negative.
+///
+/// See `Incr` for the handling of missing keys and invalid values.
+#[derive(Debug)]
+pub struct DecrBy {
+    /// Name of the key to decrement
+    key: String,
+
+    /// Amount subtracted from the value
+    decrement: i64,
+}
+
+impl Incr {
+    /// Create a new `Incr` command which increments `key`.
+    pub(crate) fn new(key: impl ToString) -> Incr {
+        Incr {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse an `Incr` instance from a received frame.
+    ///
+    /// The `INCR` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// INCR key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Incr, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Incr { key })
+    }
+
+    /// Apply the `Incr` command to the specified `Db` instance.
+    ///
+    /// The new value is written to `dst`.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        apply_delta(db, dst, &self.key, 1).await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding an `Incr` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("incr".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
+
+impl Decr {
+    /// Create a new `Decr` command which decrements `key`.
+    pub(crate) fn new(key: impl ToString) -> Decr {
+        Decr {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Decr` instance from a received frame.
+    ///
+    /// The `DECR` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// DECR key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Decr, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Decr { key })
+    }
+
+    /// Apply the `Decr` command to the specified `Db` instance.
+    ///
+    /// The new value is written to `dst`.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        apply_delta(db, dst, &self.key, -1).await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Decr` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("decr".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
+
+impl IncrBy {
+    /// Create a new `IncrBy` command which adds `increment` to `key`.
+    pub(crate) fn new(key: impl ToString, increment: i64) -> IncrBy {
+        IncrBy {
+            key: key.to_string(),
+            increment,
+        }
+    }
+
+    /// Parse an `IncrBy` instance from a received frame.
+    ///
+    /// The `INCRBY` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// INCRBY key increment
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<IncrBy, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let increment = parse.next_signed_int()?;
+
+        Ok(IncrBy { key, increment })
+    }
+
+    /// Apply the `IncrBy` command to the specified `Db` instance.
+    ///
+    /// The new value is written to `dst`.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        apply_delta(db, dst, &self.key, self.increment).await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding an `IncrBy` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("incrby".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.increment.to_string()))?;
+        Ok(frame)
+    }
+}
+
+impl DecrBy {
+    /// Create a new `DecrBy` command which subtracts `decrement` from `key`.
+    pub(crate) fn new(key: impl ToString, decrement: i64) -> DecrBy {
+        DecrBy {
+            key: key.to_string(),
+            decrement,
+        }
+    }
+
+    /// Parse a `DecrBy` instance from a received frame.
+    ///
+    /// The `DECRBY` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// DECRBY key decrement
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<DecrBy, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let decrement = parse.next_signed_int()?;
+
+        Ok(DecrBy { key, decrement })
+    }
+
+    /// Apply the `DecrBy` command to the specified `Db` instance.
+    ///
+    /// The new value is written to `dst`.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        // `i64::MIN` cannot be negated.
+        match self.decrement.checked_neg() {
+            Some(delta) => apply_delta(db, dst, &self.key, delta).await,
+            None => {
+                let response = Frame::Error("ERR decrement would overflow".into());
+                dst.write_frame(&response).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `DecrBy` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("decrby".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.decrement.to_string()))?;
+        Ok(frame)
+    }
+}
+
+/// Adds `delta` to the integer value of `key`, and writes the new value to
+/// `dst`, or the Redis error if the value is not an integer or the result
+/// overflows.
+async fn apply_delta(
+    db: &Db,
+    dst: &mut Connection,
+    key: &str,
+    delta: i64,
+) -> Result<(), MiniRedisConnectionError> {
+    let response = match db.incr_by(key, delta) {
+        Some(value) => Frame::Integer(value),
+        None => Frame::Error("ERR value is not an integer or out of range".into()),
+    };
+
+    debug!("incr command applied resp: {:?}", response);
+
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Returns all keys matching a glob-style pattern.
+///
+/// The pattern supports `*`, `?`, `[...]` classes and `\` escapes. The whole
+/// keyspace is scanned under the store lock, so this is meant for debugging
+/// rather than production use.
+#[derive(Debug)]
+pub struct Keys {
+    /// Glob-style pattern the keys must match
+    pattern: String,
+}
+
+impl Keys {
+    /// Create a new `Keys` command which lists the keys matching `pattern`.
+    pub(crate) fn new(pattern: impl ToString) -> Keys {
+        Keys {
+            pattern: pattern.to_string(),
+        }
+    }
+
+    /// Parse a `Keys` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `KEYS` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Keys` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// KEYS pattern
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Keys, MiniRedisParseError> {
+        let pattern = parse.next_string()?;
+
+        Ok(Keys { pattern })
+    }
+
+    /// Apply the `Keys` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = match db.keys(&self.pattern) {
+            Some(keys) => Frame::Array(
+                keys.into_iter()
+                    .map(|key| Frame::Bulk(Bytes::from(key.into_bytes())))
+                    .collect(),
+            ),
+            None => Frame::Error("ERR command exceeded time budget".to_string()),
+        };
+
+        debug!("keys command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Keys` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("keys".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.pattern.into_bytes()))?;
+        Ok(frame)
+    }
+}
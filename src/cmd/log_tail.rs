@@ -0,0 +1,109 @@
+use bytes::Bytes;
+use log::Level;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::logger::{self, TailRecord};
+use crate::server::shutdown::Shutdown;
+use crate::storage::db::Db;
+
+/// Turns the connection into a tail of the server log, for remote debugging.
+///
+/// Every record logged at `level` or above is sent to the client, until it
+/// disconnects. Like the `DEBUG` subcommands, it is only available when
+/// `ServerConfig::debug_commands` is set.
+#[derive(Debug)]
+pub struct LogTail {
+    level: Level,
+}
+
+impl LogTail {
+    /// Parse a `LogTail` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `LOGTAIL` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `LogTail` value is returned. If the frame is malformed
+    /// or the level is unknown, `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing one or two entries. The level
+    /// defaults to `info`.
+    ///
+    /// ```text
+    /// LOGTAIL [error|warn|info|debug|trace]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<LogTail, MiniRedisParseError> {
+        let level = match parse.next_string() {
+            Ok(level) => level
+                .parse()
+                .map_err(|_| MiniRedisParseError::Parse(format!("unknown level '{}'", level)))?,
+            Err(MiniRedisParseError::EndOfStream) => Level::Info,
+            Err(err) => return Err(err),
+        };
+
+        Ok(LogTail { level })
+    }
+
+    /// Apply the `LogTail` command.
+    ///
+    /// Replies `OK`, then sends each record as an array frame of the form
+    /// `[ "log", level, "<target> - <message>" ]`. Records missed because the
+    /// client is too slow are skipped. Nothing is logged while sending
+    /// records, which would feed records back into the tail.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+    ) -> Result<(), MiniRedisConnectionError> {
+        if !db.config().debug_commands {
+            let response = Frame::Error("ERR LOGTAIL command not allowed".into());
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+
+        let mut records = logger::tail();
+        dst.write_frame(&Frame::Simple("OK".into())).await?;
+
+        loop {
+            select! {
+                res = records.recv() => match res {
+                    Ok(record) if record.level <= self.level => {
+                        dst.write_frame(&make_record_frame(record)?).await?;
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                res = dst.read_frame() => match res? {
+                    // The tail is ended by disconnecting.
+                    Some(_) => {
+                        let response = Frame::Error("ERR only disconnecting ends LOGTAIL".into());
+                        dst.write_frame(&response).await?;
+                    }
+                    None => return Ok(()),
+                },
+                _ = shutdown.recv() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Creates the frame sending a log record to a tail.
+fn make_record_frame(record: TailRecord) -> Result<Frame, MiniRedisParseError> {
+    let mut frame = Frame::array();
+    frame.push_bulk(Bytes::from_static(b"log"))?;
+    frame.push_bulk(Bytes::from(record.level.as_str().to_lowercase()))?;
+    frame.push_bulk(Bytes::from(record.line))?;
+    Ok(frame)
+}
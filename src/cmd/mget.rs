@@ -0,0 +1,106 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Get the values of all the specified keys.
+///
+/// For every key that does not exist, the special value nil is returned, so
+/// that the reply has one entry per key, in order.
+#[derive(Debug)]
+pub struct Mget {
+    /// Names of the keys to get
+    keys: Vec<String>,
+}
+
+impl Mget {
+    /// Create a new `Mget` command which fetches `keys`.
+    pub(crate) fn new(keys: &[&str]) -> Mget {
+        Mget {
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    /// Parse a `Mget` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `MGET` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Mget` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two or more entries.
+    ///
+    /// ```text
+    /// MGET key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Mget, MiniRedisParseError> {
+        // At least one key is required.
+        let mut keys = vec![parse.next_string()?];
+
+        loop {
+            match parse.next_string() {
+                Ok(key) => keys.push(key),
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Mget { keys })
+    }
+
+    /// Apply the `Mget` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        // All values are read under a single acquisition of the store lock,
+        // so that they are consistent with each other.
+        let response = Frame::Array(
+            db.mget(&self.keys)
+                .into_iter()
+                .map(|value| match value {
+                    Some(value) => Frame::Bulk(value),
+                    None => Frame::Null,
+                })
+                .collect(),
+        );
+
+        debug!("mget command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Mget` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("mget".as_bytes()))?;
+        for key in self.keys {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+        }
+        Ok(frame)
+    }
+}
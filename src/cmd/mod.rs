@@ -1,8 +1,34 @@
+use crate::cmd::append::Append;
+use crate::cmd::client_list::ClientList;
+use crate::cmd::cluster::Cluster;
+use crate::cmd::command::CommandInfo;
+use crate::cmd::dbsize::DbSize;
+use crate::cmd::debug::DebugCommand;
+use crate::cmd::del::Del;
+use crate::cmd::echo::Echo;
+use crate::cmd::exists::Exists;
+use crate::cmd::expire::{Expire, PExpire};
+use crate::cmd::flushdb::FlushDb;
 use crate::cmd::get::Get;
+use crate::cmd::getrange::GetRange;
+use crate::cmd::getset::GetSet;
+use crate::cmd::incr::{Decr, DecrBy, Incr, IncrBy};
+use crate::cmd::keys::Keys;
+use crate::cmd::log_tail::LogTail;
+use crate::cmd::mget::Mget;
+use crate::cmd::mpublish::MPublish;
+use crate::cmd::mset::Mset;
+use crate::cmd::persist::Persist;
 use crate::cmd::ping::Ping;
 use crate::cmd::publish::Publish;
+use crate::cmd::rename::{Rename, RenameNx};
+use crate::cmd::scan::Scan;
 use crate::cmd::set::Set;
+use crate::cmd::setnx::SetNx;
+use crate::cmd::strlen::Strlen;
 use crate::cmd::subscribe::Subscribe;
+use crate::cmd::ttl::{Pttl, Ttl};
+use crate::cmd::type_cmd::Type;
 use crate::cmd::unknown::Unknown;
 use crate::cmd::unsubscribe::Unsubscribe;
 use crate::connection::connect::Connection;
@@ -12,11 +38,38 @@ use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
 use crate::server::shutdown::Shutdown;
 use crate::storage::db::Db;
 
+pub(crate) mod append;
+pub(crate) mod arity;
+pub(crate) mod client_list;
+pub(crate) mod cluster;
+pub(crate) mod command;
+pub(crate) mod dbsize;
+pub(crate) mod debug;
+pub(crate) mod del;
+pub(crate) mod echo;
+pub(crate) mod exists;
+pub(crate) mod expire;
+pub(crate) mod flushdb;
 pub(crate) mod get;
+pub(crate) mod getrange;
+pub(crate) mod getset;
+pub(crate) mod incr;
+pub(crate) mod keys;
+pub(crate) mod log_tail;
+pub(crate) mod mget;
+pub(crate) mod mpublish;
+pub(crate) mod mset;
+pub(crate) mod persist;
 pub(crate) mod ping;
 pub(crate) mod publish;
+pub(crate) mod rename;
+pub(crate) mod scan;
 pub(crate) mod set;
+pub(crate) mod setnx;
+pub(crate) mod strlen;
 pub(crate) mod subscribe;
+pub(crate) mod ttl;
+pub(crate) mod type_cmd;
 pub(crate) mod unknown;
 pub(crate) mod unsubscribe;
 
@@ -26,11 +79,43 @@ pub(crate) mod unsubscribe;
 #[derive(Debug)]
 pub enum Command {
     Get(Get),
+    GetRange(GetRange),
+    Mget(Mget),
+    Strlen(Strlen),
     Set(Set),
+    Mset(Mset),
+    SetNx(SetNx),
+    Append(Append),
+    GetSet(GetSet),
+    Del(Del),
+    Exists(Exists),
+    Expire(Expire),
+    PExpire(PExpire),
+    Persist(Persist),
+    Ttl(Ttl),
+    Pttl(Pttl),
+    Type(Type),
+    Keys(Keys),
+    Scan(Scan),
+    DbSize(DbSize),
+    FlushDb(FlushDb),
+    Rename(Rename),
+    RenameNx(RenameNx),
+    Incr(Incr),
+    Decr(Decr),
+    IncrBy(IncrBy),
+    DecrBy(DecrBy),
     Publish(Publish),
+    MPublish(MPublish),
     Subscribe(Subscribe),
     Unsubscribe(Unsubscribe),
     Ping(Ping),
+    Echo(Echo),
+    ClientList(ClientList),
+    Cluster(Cluster),
+    Debug(DebugCommand),
+    CommandInfo(CommandInfo),
+    LogTail(LogTail),
     Unknown(Unknown),
 }
 
@@ -56,15 +141,59 @@ impl Command {
         // matching.
         let command_name = parse.next_string()?.to_lowercase();
 
+        // Reject a wrong number of arguments up front, so that every command
+        // reports it consistently.
+        arity::check(&command_name, parse.remaining())?;
+
         // Match the command name, delegating the rest of the parsing to the
         // specific command.
         let command = match &command_name[..] {
             "get" => Command::Get(Get::parse_frames(&mut parse)?),
+            "getrange" => Command::GetRange(GetRange::parse_frames(&mut parse)?),
+            "mget" => Command::Mget(Mget::parse_frames(&mut parse)?),
+            "strlen" => Command::Strlen(Strlen::parse_frames(&mut parse)?),
             "set" => Command::Set(Set::parse_frames(&mut parse)?),
+            "mset" => Command::Mset(Mset::parse_frames(&mut parse)?),
+            "setnx" => Command::SetNx(SetNx::parse_frames(&mut parse)?),
+            "append" => Command::Append(Append::parse_frames(&mut parse)?),
+            "getset" => Command::GetSet(GetSet::parse_frames(&mut parse)?),
+            "del" => Command::Del(Del::parse_frames(&mut parse)?),
+            "exists" => Command::Exists(Exists::parse_frames(&mut parse)?),
+            "expire" => Command::Expire(Expire::parse_frames(&mut parse)?),
+            "pexpire" => Command::PExpire(PExpire::parse_frames(&mut parse)?),
+            "persist" => Command::Persist(Persist::parse_frames(&mut parse)?),
+            "ttl" => Command::Ttl(Ttl::parse_frames(&mut parse)?),
+            "pttl" => Command::Pttl(Pttl::parse_frames(&mut parse)?),
+            "type" => Command::Type(Type::parse_frames(&mut parse)?),
+            "keys" => Command::Keys(Keys::parse_frames(&mut parse)?),
+            "scan" => Command::Scan(Scan::parse_frames(&mut parse)?),
+            "dbsize" => Command::DbSize(DbSize::parse_frames(&mut parse)?),
+            "flushdb" => Command::FlushDb(FlushDb::parse_frames(&mut parse)?),
+            "rename" => Command::Rename(Rename::parse_frames(&mut parse)?),
+            "renamenx" => Command::RenameNx(RenameNx::parse_frames(&mut parse)?),
+            "incr" => Command::Incr(Incr::parse_frames(&mut parse)?),
+            "decr" => Command::Decr(Decr::parse_frames(&mut parse)?),
+            "incrby" => Command::IncrBy(IncrBy::parse_frames(&mut parse)?),
+            "decrby" => Command::DecrBy(DecrBy::parse_frames(&mut parse)?),
             "publish" => Command::Publish(Publish::parse_frames(&mut parse)?),
+            "mpublish" => Command::MPublish(MPublish::parse_frames(&mut parse)?),
             "subscribe" => Command::Subscribe(Subscribe::parse_frames(&mut parse)?),
             "unsubscribe" => Command::Unsubscribe(Unsubscribe::parse_frames(&mut parse)?),
             "ping" => Command::Ping(Ping::parse_frames(&mut parse)?),
+            "echo" => Command::Echo(Echo::parse_frames(&mut parse)?),
+            "client" => match &parse.next_string()?.to_lowercase()[..] {
+                "list" => Command::ClientList(ClientList::parse_frames(&mut parse)?),
+                subcommand => {
+                    return Err(MiniRedisParseError::Parse(format!(
+                        "unknown subcommand '{}'",
+                        subcommand
+                    )))
+                }
+            },
+            "cluster" => Command::Cluster(Cluster::parse_frames(&mut parse)?),
+            "debug" => Command::Debug(DebugCommand::parse_frames(&mut parse)?),
+            "command" => Command::CommandInfo(CommandInfo::parse_frames(&mut parse)?),
+            "logtail" => Command::LogTail(LogTail::parse_frames(&mut parse)?),
             _ => {
                 // The command is not recognized and an Unknown command is
                 // returned.
@@ -103,15 +232,47 @@ impl Command {
 
         match self {
             Ping(cmd) => cmd.apply(dst).await,
+            Echo(cmd) => cmd.apply(dst).await,
             Get(cmd) => cmd.apply(db, dst).await,
+            GetRange(cmd) => cmd.apply(db, dst).await,
+            Mget(cmd) => cmd.apply(db, dst).await,
+            Strlen(cmd) => cmd.apply(db, dst).await,
             Set(cmd) => cmd.apply(db, dst).await,
+            Mset(cmd) => cmd.apply(db, dst).await,
+            SetNx(cmd) => cmd.apply(db, dst).await,
+            Append(cmd) => cmd.apply(db, dst).await,
+            GetSet(cmd) => cmd.apply(db, dst).await,
+            Del(cmd) => cmd.apply(db, dst).await,
+            Exists(cmd) => cmd.apply(db, dst).await,
+            Expire(cmd) => cmd.apply(db, dst).await,
+            PExpire(cmd) => cmd.apply(db, dst).await,
+            Persist(cmd) => cmd.apply(db, dst).await,
+            Ttl(cmd) => cmd.apply(db, dst).await,
+            Pttl(cmd) => cmd.apply(db, dst).await,
+            Type(cmd) => cmd.apply(db, dst).await,
+            Keys(cmd) => cmd.apply(db, dst).await,
+            Scan(cmd) => cmd.apply(db, dst).await,
+            DbSize(cmd) => cmd.apply(db, dst).await,
+            FlushDb(cmd) => cmd.apply(db, dst).await,
+            Rename(cmd) => cmd.apply(db, dst).await,
+            RenameNx(cmd) => cmd.apply(db, dst).await,
+            Incr(cmd) => cmd.apply(db, dst).await,
+            Decr(cmd) => cmd.apply(db, dst).await,
+            IncrBy(cmd) => cmd.apply(db, dst).await,
+            DecrBy(cmd) => cmd.apply(db, dst).await,
             Publish(cmd) => cmd.apply(db, dst).await,
+            MPublish(cmd) => cmd.apply(db, dst).await,
             Subscribe(cmd) => cmd.apply(db, dst, shutdown).await,
             // `Unsubscribe` cannot be applied. It may only be received from the
             // context of a `Subscribe` command.
             Unsubscribe(_) => Err(MiniRedisConnectionError::CommandExecute(
                 "`Unsubscribe` is unsupported in this context".into(),
             )),
+            ClientList(cmd) => cmd.apply(db, dst).await,
+            Cluster(cmd) => cmd.apply(db, dst).await,
+            Debug(cmd) => cmd.apply(db, dst).await,
+            CommandInfo(cmd) => cmd.apply(dst).await,
+            LogTail(cmd) => cmd.apply(db, dst, shutdown).await,
             Unknown(cmd) => cmd.apply(dst).await,
         }
     }
@@ -120,11 +281,43 @@ impl Command {
     pub(crate) fn get_name(&self) -> &str {
         match self {
             Command::Get(_) => "get",
+            Command::GetRange(_) => "getrange",
+            Command::Mget(_) => "mget",
+            Command::Strlen(_) => "strlen",
             Command::Set(_) => "set",
+            Command::Mset(_) => "mset",
+            Command::SetNx(_) => "setnx",
+            Command::Append(_) => "append",
+            Command::GetSet(_) => "getset",
+            Command::Del(_) => "del",
+            Command::Exists(_) => "exists",
+            Command::Expire(_) => "expire",
+            Command::PExpire(_) => "pexpire",
+            Command::Persist(_) => "persist",
+            Command::Ttl(_) => "ttl",
+            Command::Pttl(_) => "pttl",
+            Command::Type(_) => "type",
+            Command::Keys(_) => "keys",
+            Command::Scan(_) => "scan",
+            Command::DbSize(_) => "dbsize",
+            Command::FlushDb(_) => "flushdb",
+            Command::Rename(_) => "rename",
+            Command::RenameNx(_) => "renamenx",
+            Command::Incr(_) => "incr",
+            Command::Decr(_) => "decr",
+            Command::IncrBy(_) => "incrby",
+            Command::DecrBy(_) => "decrby",
             Command::Publish(_) => "pub",
+            Command::MPublish(_) => "mpublish",
             Command::Subscribe(_) => "subscribe",
             Command::Unsubscribe(_) => "unsubscribe",
             Command::Ping(_) => "ping",
+            Command::Echo(_) => "echo",
+            Command::ClientList(_) => "client",
+            Command::Cluster(_) => "cluster",
+            Command::Debug(_) => "debug",
+            Command::CommandInfo(_) => "command",
+            Command::LogTail(_) => "logtail",
             Command::Unknown(cmd) => cmd.get_name(),
         }
     }
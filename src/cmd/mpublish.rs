@@ -0,0 +1,126 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Posts messages to several channels at once.
+///
+/// This is a mini-redis extension, not a standard Redis command. All messages
+/// are published under a single acquisition of the store lock, so an event
+/// that must reach several channels does so atomically and in one round trip.
+#[derive(Debug)]
+pub struct MPublish {
+    /// The `(channel, message)` pairs to publish, in order.
+    messages: Vec<(String, Bytes)>,
+}
+
+impl MPublish {
+    /// Create a new `MPublish` command which sends each message on its
+    /// channel.
+    pub(crate) fn new(messages: Vec<(String, Bytes)>) -> MPublish {
+        MPublish { messages }
+    }
+
+    /// Parse a `MPublish` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `MPUBLISH` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `MPublish` value is returned. If the frame is
+    /// malformed, `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing one or more channel/message pairs.
+    ///
+    /// ```text
+    /// MPUBLISH channel message [channel message ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<MPublish, MiniRedisParseError> {
+        // At least one pair is required.
+        let mut messages = vec![(parse.next_string()?, parse.next_bytes()?)];
+
+        loop {
+            let channel = match parse.next_string() {
+                Ok(channel) => channel,
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            };
+
+            // A channel must always be followed by its message.
+            let message = match parse.next_bytes() {
+                Ok(message) => message,
+                Err(MiniRedisParseError::EndOfStream) => {
+                    return Err(MiniRedisParseError::Parse(
+                        "`MPUBLISH` expects channel and message pairs".into(),
+                    ))
+                }
+                Err(err) => return Err(err),
+            };
+
+            messages.push((channel, message));
+        }
+
+        Ok(MPublish { messages })
+    }
+
+    /// Apply the `MPublish` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        // In strict mode, nothing is published if any channel is undeclared.
+        if let Some((channel, _)) = self
+            .messages
+            .iter()
+            .find(|(channel, _)| !db.is_channel_allowed(channel))
+        {
+            let response = Frame::Error(format!("ERR unknown channel '{}'", channel));
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+
+        // The number of subscribers of each channel is returned, in the order
+        // the channels were given.
+        let mut response = Frame::array();
+        for num_subscribers in db.publish_many(self.messages) {
+            response.push_int(num_subscribers as i64)?;
+        }
+        debug!("apply command applied response: {}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `MPublish` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("mpublish".as_bytes()))?;
+        for (channel, message) in self.messages {
+            frame.push_bulk(Bytes::from(channel.into_bytes()))?;
+            frame.push_bulk(message)?;
+        }
+
+        Ok(frame)
+    }
+}
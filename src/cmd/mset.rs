@@ -0,0 +1,111 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Sets the given keys to their respective values.
+///
+/// All keys are set under a single acquisition of the store lock, so no client
+/// sees some of the keys updated and others not. As with Redis, the keys are
+/// set without a time to live, discarding any previous one.
+#[derive(Debug)]
+pub struct Mset {
+    /// The `(key, value)` pairs to set, in order.
+    pairs: Vec<(String, Bytes)>,
+}
+
+impl Mset {
+    /// Create a new `Mset` command which sets each key to its value.
+    pub(crate) fn new(pairs: Vec<(String, Bytes)>) -> Mset {
+        Mset { pairs }
+    }
+
+    /// Parse a `Mset` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `MSET` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// On success, the `Mset` value is returned. If the frame is malformed,
+    /// `Err` is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing one or more key/value pairs.
+    ///
+    /// ```text
+    /// MSET key value [key value ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Mset, MiniRedisParseError> {
+        // At least one pair is required.
+        let mut pairs = vec![];
+
+        loop {
+            let key = match parse.next_string() {
+                Ok(key) => key,
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) if !pairs.is_empty() => break,
+                Err(err) => return Err(err),
+            };
+
+            // A key must always be followed by its value.
+            let value = match parse.next_bytes() {
+                Ok(value) => value,
+                Err(MiniRedisParseError::EndOfStream) => {
+                    return Err(MiniRedisParseError::Parse(
+                        "`MSET` expects key and value pairs".into(),
+                    ))
+                }
+                Err(err) => return Err(err),
+            };
+
+            pairs.push((key, value));
+        }
+
+        Ok(Mset { pairs })
+    }
+
+    /// Apply the `Mset` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        db.mset(self.pairs);
+
+        let response = Frame::Simple("OK".to_string());
+        debug!("mset command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Mset` command to send to
+    /// the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("mset".as_bytes()))?;
+        for (key, value) in self.pairs {
+            frame.push_bulk(Bytes::from(key.into_bytes()))?;
+            frame.push_bulk(value)?;
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,83 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Remove the existing timeout on key, making it persistent.
+///
+/// Replies 1 if the timeout was removed, 0 if the key does not exist or has no
+/// timeout.
+#[derive(Debug)]
+pub struct Persist {
+    /// Name of the key
+    key: String,
+}
+
+impl Persist {
+    /// Create a new `Persist` command which removes the timeout of `key`.
+    pub(crate) fn new(key: impl ToString) -> Persist {
+        Persist {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Persist` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `PERSIST` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Persist` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// PERSIST key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Persist, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Persist { key })
+    }
+
+    /// Apply the `Persist` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Integer(db.persist(&self.key) as i64);
+
+        debug!("persist command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Persist` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("persist".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
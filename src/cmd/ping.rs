@@ -65,6 +65,24 @@ impl Ping {
         Ok(())
     }
 
+    /// Apply the `Ping` command received while the connection is in
+    /// subscribe mode.
+    ///
+    /// As in Redis, the reply is then an array made of `pong` and the message,
+    /// which is empty when none was provided.
+    pub(crate) async fn apply_subscribed(
+        self,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let mut response = Frame::array();
+        response.push_bulk(Bytes::from_static(b"pong"))?;
+        response.push_bulk(Bytes::from(self.msg.unwrap_or_default()))?;
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
     /// Converts the command into an equivalent `Frame`.
     ///
     /// This is called by the client when encoding a `Ping` command to send
@@ -74,6 +74,14 @@ impl Publish {
         db: &Db,
         dst: &mut Connection,
     ) -> Result<(), MiniRedisConnectionError> {
+        // In strict mode, publishing to an undeclared channel is an error.
+        if !db.is_channel_allowed(&self.channel) {
+            let response = Frame::Error(format!("ERR unknown channel '{}'", self.channel));
+            debug!("apply command applied response: {}", response);
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+
         // The shared state contains the `tokio::sync::broadcast::Sender` for
         // all active channels. Calling `db.publish` dispatches the message into
         // the appropriate channel.
@@ -87,7 +95,7 @@ impl Publish {
 
         // The number of subscribers is returned as the response to the publish
         // request.
-        let response = Frame::Integer(num_subscribers as u64);
+        let response = Frame::Integer(num_subscribers as i64);
         debug!("apply command applied response: {}", response);
 
         // Write the frame to the client.
@@ -0,0 +1,167 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Renames `key` to `new_key`, overwriting any value of `new_key`.
+///
+/// The time to live of `key` moves along with its value. Replies an error if
+/// `key` does not exist. Renaming a key onto itself does nothing.
+#[derive(Debug)]
+pub struct Rename {
+    /// Name of the key to rename
+    key: String,
+
+    /// The new name of the key
+    new_key: String,
+}
+
+/// Renames `key` to `new_key`, only if `new_key` does not exist.
+///
+/// Replies 1 if the key was renamed, 0 if `new_key` already held a value, or
+/// an error if `key` does not exist.
+#[derive(Debug)]
+pub struct RenameNx {
+    /// Name of the key to rename
+    key: String,
+
+    /// The new name of the key
+    new_key: String,
+}
+
+impl Rename {
+    /// Create a new `Rename` command which renames `key` to `new_key`.
+    pub(crate) fn new(key: impl ToString, new_key: impl ToString) -> Rename {
+        Rename {
+            key: key.to_string(),
+            new_key: new_key.to_string(),
+        }
+    }
+
+    /// Parse a `Rename` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `RENAME` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Rename` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// RENAME key newkey
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Rename, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let new_key = parse.next_string()?;
+
+        Ok(Rename { key, new_key })
+    }
+
+    /// Apply the `Rename` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = match db.rename(&self.key, &self.new_key, false) {
+            Some(_) => Frame::Simple("OK".to_string()),
+            None => Frame::Error("ERR no such key".to_string()),
+        };
+
+        debug!("rename command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Rename` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("rename".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.new_key.into_bytes()))?;
+        Ok(frame)
+    }
+}
+
+impl RenameNx {
+    /// Create a new `RenameNx` command which renames `key` to `new_key` if
+    /// `new_key` is missing.
+    pub(crate) fn new(key: impl ToString, new_key: impl ToString) -> RenameNx {
+        RenameNx {
+            key: key.to_string(),
+            new_key: new_key.to_string(),
+        }
+    }
+
+    /// Parse a `RenameNx` instance from a received frame.
+    ///
+    /// The `RENAMENX` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// RENAMENX key newkey
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<RenameNx, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let new_key = parse.next_string()?;
+
+        Ok(RenameNx { key, new_key })
+    }
+
+    /// Apply the `RenameNx` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = match db.rename(&self.key, &self.new_key, true) {
+            Some(renamed) => Frame::Integer(renamed as i64),
+            None => Frame::Error("ERR no such key".to_string()),
+        };
+
+        debug!("renamenx command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `RenameNx` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("renamenx".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(Bytes::from(self.new_key.into_bytes()))?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,143 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Number of keys per page of a `SCAN` call without a `COUNT` option.
+const DEFAULT_COUNT: usize = 10;
+
+/// Incrementally iterates over the keys.
+///
+/// Each call returns a page of keys along with the cursor to pass to the next
+/// call. A cursor of 0 starts a new iteration, and a returned cursor of 0 means
+/// the iteration is complete. `COUNT` bounds the size of a page, not the work
+/// done: each call still walks the whole keyspace under the read lock, so a
+/// full iteration costs about N²/`COUNT` for N keys.
+///
+/// As with Redis, a key present during the whole iteration is returned,
+/// while keys added or removed meanwhile may or may not be. `MATCH` filters
+/// the keys of a page, so a page may be empty before the iteration completes.
+#[derive(Debug)]
+pub struct Scan {
+    /// Cursor returned by the previous call, 0 to start
+    cursor: u64,
+
+    /// Glob-style pattern the returned keys must match
+    pattern: Option<String>,
+
+    /// Number of keys per page, before `MATCH` filtering
+    count: Option<usize>,
+}
+
+impl Scan {
+    /// Create a new `Scan` command resuming the iteration at `cursor`.
+    pub(crate) fn new(cursor: u64, pattern: Option<&str>, count: Option<usize>) -> Scan {
+        Scan {
+            cursor,
+            pattern: pattern.map(|pattern| pattern.to_string()),
+            count,
+        }
+    }
+
+    /// Parse a `Scan` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `SCAN` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Scan` value on success. If the frame is malformed, `Err` is
+    /// returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two to six entries.
+    ///
+    /// ```text
+    /// SCAN cursor [MATCH pattern] [COUNT count]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Scan, MiniRedisParseError> {
+        let cursor = parse.next_int()?;
+        let mut pattern = None;
+        let mut count = None;
+
+        loop {
+            match parse.next_string() {
+                Ok(option) if option.eq_ignore_ascii_case("match") => {
+                    pattern = Some(parse.next_string()?);
+                }
+                Ok(option) if option.eq_ignore_ascii_case("count") => match parse.next_int()? {
+                    0 => return Err(MiniRedisParseError::Parse("syntax error".into())),
+                    n => count = Some(n as usize),
+                },
+                Ok(_) => return Err(MiniRedisParseError::Parse("syntax error".into())),
+                // The `EndOfStream` error indicates there is no further data to
+                // parse.
+                Err(MiniRedisParseError::EndOfStream) => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Scan {
+            cursor,
+            pattern,
+            count,
+        })
+    }
+
+    /// Apply the `Scan` command to the specified `Db` instance.
+    ///
+    /// The response is the array `[next_cursor, [key ...]]`, the cursor being
+    /// sent as a bulk string as Redis does.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let count = self.count.unwrap_or(DEFAULT_COUNT);
+        let response = match db.scan(self.cursor, self.pattern.as_deref(), count) {
+            Some((cursor, keys)) => Frame::Array(vec![
+                Frame::Bulk(Bytes::from(cursor.to_string())),
+                Frame::Array(
+                    keys.into_iter()
+                        .map(|key| Frame::Bulk(Bytes::from(key.into_bytes())))
+                        .collect(),
+                ),
+            ]),
+            None => Frame::Error("ERR command exceeded time budget".to_string()),
+        };
+
+        debug!("scan command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Scan` command to send to
+    /// the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("scan".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.cursor.to_string()))?;
+        if let Some(pattern) = self.pattern {
+            frame.push_bulk(Bytes::from("match".as_bytes()))?;
+            frame.push_bulk(Bytes::from(pattern.into_bytes()))?;
+        }
+        if let Some(count) = self.count {
+            frame.push_bulk(Bytes::from("count".as_bytes()))?;
+            frame.push_int(count as i64)?;
+        }
+        Ok(frame)
+    }
+}
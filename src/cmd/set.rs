@@ -123,11 +123,16 @@ impl Set {
         db: &Db,
         dst: &mut Connection,
     ) -> Result<(), MiniRedisConnectionError> {
-        // Set the value in the shared database state.
-        db.set(self.key, self.value, self.expire);
-
-        // Create a success response and write it to `dst`.
-        let response = Frame::Simple("OK".to_string());
+        // Set the value in the shared database state. As with Redis, a zero
+        // time to live is rejected, as is one whose expiration would overflow.
+        let set = self.expire != Some(Duration::ZERO) && db.set(self.key, self.value, self.expire);
+
+        // Create the response and write it to `dst`.
+        let response = if set {
+            Frame::Simple("OK".to_string())
+        } else {
+            Frame::Error("ERR invalid expire time in 'set' command".to_string())
+        };
         debug!("applied set command response: {:?}", response);
 
         dst.write_frame(&response).await?;
@@ -152,7 +157,7 @@ impl Set {
             // src/bin/cli.rs parses the expiration argument as milliseconds
             // in duration_from_ms_str()
             frame.push_bulk(Bytes::from("px".as_bytes()))?;
-            frame.push_int(ms.as_millis() as u64)?;
+            frame.push_int(ms.as_millis() as i64)?;
         }
         Ok(frame)
     }
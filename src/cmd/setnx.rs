@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Set `key` to hold `value` only if the key does not exist.
+///
+/// Replies 1 if the key was set, 0 if it already held a value. A key which
+/// expired counts as missing.
+#[derive(Debug)]
+pub struct SetNx {
+    /// Name of the key to set
+    key: String,
+
+    /// The new value
+    value: Bytes,
+}
+
+impl SetNx {
+    /// Create a new `SetNx` command which sets `key` to `value` if missing.
+    pub(crate) fn new(key: impl ToString, value: Bytes) -> SetNx {
+        SetNx {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    /// Parse a `SetNx` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `SETNX` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `SetNx` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing three entries.
+    ///
+    /// ```text
+    /// SETNX key value
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<SetNx, MiniRedisParseError> {
+        let key = parse.next_string()?;
+        let value = parse.next_bytes()?;
+
+        Ok(SetNx { key, value })
+    }
+
+    /// Apply the `SetNx` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Integer(db.set_nx(self.key, self.value) as i64);
+
+        debug!("setnx command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `SetNx` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("setnx".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        frame.push_bulk(self.value)?;
+        Ok(frame)
+    }
+}
@@ -0,0 +1,82 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Returns the length in bytes of the value stored at key.
+///
+/// The length of a missing key is 0.
+#[derive(Debug)]
+pub struct Strlen {
+    /// Name of the key
+    key: String,
+}
+
+impl Strlen {
+    /// Create a new `Strlen` command which measures the value of `key`.
+    pub(crate) fn new(key: impl ToString) -> Strlen {
+        Strlen {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Strlen` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `STRLEN` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Strlen` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// STRLEN key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Strlen, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Strlen { key })
+    }
+
+    /// Apply the `Strlen` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Integer(db.strlen(&self.key) as i64);
+
+        debug!("strlen command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Strlen` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("strlen".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
@@ -176,6 +176,25 @@ impl Subscribe {
         db: &Db,
         dst: &mut Connection,
     ) -> Result<(), MiniRedisConnectionError> {
+        // In strict mode, subscribing to an undeclared channel is rejected
+        // without affecting the existing subscriptions.
+        if !db.is_channel_allowed(&channel_name) {
+            warn!("subscribe to undeclared channel: {}", channel_name);
+            let response = Frame::Error(format!("ERR unknown channel '{}'", channel_name));
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+
+        // Subscribing again to a channel does not add a subscription.
+        if let Some(max) = db.config().max_subscriptions_per_connection {
+            if subscriptions.len() >= max && !subscriptions.contains_key(&channel_name) {
+                warn!("max subscriptions reached, subscribe to: {}", channel_name);
+                let response = Frame::Error("ERR max subscriptions reached".into());
+                dst.write_frame(&response).await?;
+                return Ok(());
+            }
+        }
+
         let mut rx = db.subscribe(channel_name.clone());
 
         // Subscribe to the channel.
@@ -221,7 +240,7 @@ fn make_subscribe_frame(
     let mut response = Frame::array();
     response.push_bulk(Bytes::from_static(b"subscribe"))?;
     response.push_bulk(Bytes::from(channel_name))?;
-    response.push_int(num_subs as u64)?;
+    response.push_int(num_subs as i64)?;
     Ok(response)
 }
 
@@ -235,8 +254,8 @@ fn make_message_frame(channel_name: String, msg: Bytes) -> Result<Frame, MiniRed
     Ok(response)
 }
 
-/// Handle a command received while inside `Subscribe::apply`. Only subscribe
-/// and unsubscribe commands are permitted in this context.
+/// Handle a command received while inside `Subscribe::apply`. Only subscribe,
+/// unsubscribe and ping commands are permitted in this context.
 ///
 /// Any new subscriptions are appended to `subscribe_to` instead of modifying
 /// `subscriptions`.
@@ -248,13 +267,26 @@ async fn handle_command(
 ) -> Result<(), MiniRedisConnectionError> {
     // A command has been received from the client.
     //
-    // Only `SUBSCRIBE` and `UNSUBSCRIBE` commands are permitted
+    // Only `SUBSCRIBE`, `UNSUBSCRIBE` and `PING` commands are permitted
     // in this context.
-    match Command::from_frame(frame)? {
+    //
+    // As in `Handler::run`, the frame itself was well-formed, so an invalid
+    // command is reported to the client and the subscriptions are kept.
+    let command = match Command::from_frame(frame) {
+        Ok(command) => command,
+        Err(err) => {
+            debug!("invalid command while subscribed: {}", err);
+            let response = Frame::Error(format!("ERR {}", err));
+            dst.write_frame(&response).await?;
+            return Ok(());
+        }
+    };
+
+    match command {
         Command::Subscribe(subscribe) => {
             // The `apply` method will subscribe to the channels we add to this
             // vector.
-            subscribe_to.extend(subscribe.channels.into_iter());
+            subscribe_to.extend(subscribe.channels);
         }
         Command::Unsubscribe(mut unsubscribe) => {
             // If no channels are specified, this requests unsubscribing from
@@ -277,6 +309,9 @@ async fn handle_command(
                 debug!("unsubscribed success: {}", response);
             }
         }
+        Command::Ping(ping) => {
+            ping.apply_subscribed(dst).await?;
+        }
         command => {
             let cmd = Unknown::new(command.get_name());
             cmd.apply(dst).await?;
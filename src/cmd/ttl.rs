@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Returns the remaining time to live of a key, in seconds.
+///
+/// As with Redis, replies -2 if the key does not exist, and -1 if the key
+/// exists but has no associated expiration.
+#[derive(Debug)]
+pub struct Ttl {
+    /// Name of the key
+    key: String,
+}
+
+/// Returns the remaining time to live of a key, in milliseconds.
+///
+/// See `Ttl`.
+#[derive(Debug)]
+pub struct Pttl {
+    /// Name of the key
+    key: String,
+}
+
+impl Ttl {
+    /// Create a new `Ttl` command which queries `key`.
+    pub(crate) fn new(key: impl ToString) -> Ttl {
+        Ttl {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Ttl` instance from a received frame.
+    ///
+    /// The `TTL` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// TTL key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Ttl, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Ttl { key })
+    }
+
+    /// Apply the `Ttl` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        // Rounded to the nearest second, as Redis does.
+        apply_ttl(db, dst, &self.key, |ttl| {
+            ((ttl.as_millis() + 500) / 1000) as i64
+        })
+        .await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Ttl` command to send to
+    /// the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("ttl".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
+
+impl Pttl {
+    /// Create a new `Pttl` command which queries `key`.
+    pub(crate) fn new(key: impl ToString) -> Pttl {
+        Pttl {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Pttl` instance from a received frame.
+    ///
+    /// The `PTTL` string has already been consumed.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// PTTL key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Pttl, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Pttl { key })
+    }
+
+    /// Apply the `Pttl` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        apply_ttl(db, dst, &self.key, |ttl| ttl.as_millis() as i64).await
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Pttl` command to send to
+    /// the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("pttl".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
+
+/// Writes the time to live of `key` to `dst`, converted by `unit`, or the
+/// Redis special values for missing keys and keys without expiration.
+async fn apply_ttl(
+    db: &Db,
+    dst: &mut Connection,
+    key: &str,
+    unit: impl FnOnce(Duration) -> i64,
+) -> Result<(), MiniRedisConnectionError> {
+    let response = match db.ttl(key) {
+        Some(Some(ttl)) => Frame::Integer(unit(ttl)),
+        Some(None) => Frame::Integer(-1),
+        None => Frame::Integer(-2),
+    };
+
+    debug!("ttl command applied resp: {:?}", response);
+
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use bytes::Bytes;
+use log::debug;
+
+use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::connection::parse::Parse;
+use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
+use crate::storage::db::Db;
+use crate::storage::traits::KvStore;
+
+/// Returns the type of the value stored at key.
+///
+/// Only strings are supported for now, so the reply is `string`, or `none` if
+/// the key does not exist.
+#[derive(Debug)]
+pub struct Type {
+    /// Name of the key
+    key: String,
+}
+
+impl Type {
+    /// Create a new `Type` command which queries the type of `key`.
+    pub(crate) fn new(key: impl ToString) -> Type {
+        Type {
+            key: key.to_string(),
+        }
+    }
+
+    /// Parse a `Type` instance from a received frame.
+    ///
+    /// The `Parse` argument provides a cursor-like API to read fields from the
+    /// `Frame`. At this point, the entire frame has already been received from
+    /// the socket.
+    ///
+    /// The `TYPE` string has already been consumed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Type` value on success. If the frame is malformed, `Err`
+    /// is returned.
+    ///
+    /// # Format
+    ///
+    /// Expects an array frame containing two entries.
+    ///
+    /// ```text
+    /// TYPE key
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Type, MiniRedisParseError> {
+        let key = parse.next_string()?;
+
+        Ok(Type { key })
+    }
+
+    /// Apply the `Type` command to the specified `Db` instance.
+    ///
+    /// The response is written to `dst`. This is called by the server in order
+    /// to execute a received command.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+    ) -> Result<(), MiniRedisConnectionError> {
+        let response = Frame::Simple(db.key_type(&self.key).to_string());
+
+        debug!("type command applied resp: {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Converts the command into an equivalent `Frame`.
+    ///
+    /// This is called by the client when encoding a `Type` command to send
+    /// to the server.
+    pub(crate) fn into_frame(self) -> Result<Frame, MiniRedisParseError> {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("type".as_bytes()))?;
+        frame.push_bulk(Bytes::from(self.key.into_bytes()))?;
+        Ok(frame)
+    }
+}
@@ -89,6 +89,6 @@ pub(crate) fn make_unsubscribe_frame(
     let mut response = Frame::array();
     response.push_bulk(Bytes::from_static(b"unsubscribe"))?;
     response.push_bulk(Bytes::from(channel_name))?;
-    response.push_int(num_subs as u64)?;
+    response.push_int(num_subs as i64)?;
     Ok(response)
 }
@@ -1,2 +1,218 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::error::MiniRedisServerError;
+
 /// Logger level
 pub static LOG_LEVEL: &str = "LOG_LEVEL";
+
+/// Configuration for the mini-redis server.
+///
+/// Passed to [`run_with_config`](crate::server::run_with_config). The
+/// `Default` value matches the behavior of [`run`](crate::server::run).
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfig {
+    /// Only allow pub/sub on pre-declared channels.
+    ///
+    /// By default, subscribing to a channel creates it on demand and
+    /// publishing to an unknown channel silently reaches no one. When this is
+    /// `true`, publishing or subscribing to a channel not listed in
+    /// `declared_channels` is rejected with an error instead. This helps catch
+    /// typos in channel names.
+    pub strict_channels: bool,
+
+    /// The channels allowed when `strict_channels` is set.
+    pub declared_channels: HashSet<String>,
+
+    /// Yield to the scheduler after every `yield_every` commands applied on a
+    /// connection. `0` disables yielding.
+    ///
+    /// A client pipelining a large batch of commands otherwise keeps its task
+    /// busy until the batch is drained. Yielding periodically gives other
+    /// connections a chance to be serviced, improving tail latency fairness.
+    pub yield_every: u64,
+
+    /// Do not spawn the background task purging expired keys.
+    ///
+    /// Expired keys are then expired lazily: they are never returned to
+    /// clients, and are reclaimed when accessed or when a later write finds
+    /// them due. Useful for embedders that do not want `Db` to spawn a task,
+    /// e.g. on a short-lived runtime.
+    pub disable_purge_task: bool,
+
+    /// Do not remove expired keys when reading them.
+    ///
+    /// Expired keys are still never returned to clients, but reads no longer
+    /// mutate the store: expired keys are only reclaimed by the purge task or
+    /// by a later write. This trades holding on to expired keys a little
+    /// longer for reads that only need shared access to the store.
+    pub disable_lazy_expiry: bool,
+
+    /// Randomly perturb each key's expiration by up to ± this percentage of
+    /// its TTL, e.g. `Some(10.0)` for ±10%.
+    ///
+    /// Keys set with the same TTL otherwise all expire at the same instant,
+    /// causing a purge spike and a burst of cache misses. This is opt-in as
+    /// keys no longer expire after exactly the requested TTL.
+    pub ttl_jitter: Option<f64>,
+
+    /// Maximum number of channels a single connection may subscribe to.
+    ///
+    /// Each subscription holds a broadcast receiver. Subscribing to a channel
+    /// past the limit is rejected with an error, without affecting the
+    /// existing subscriptions. `None` means unlimited.
+    pub max_subscriptions_per_connection: Option<usize>,
+
+    /// Keep at most this many pub/sub channels without subscribers.
+    ///
+    /// Channels are created on subscription and are not removed when their
+    /// last subscriber goes away. With many transient channels, the registry
+    /// would grow without bound. When set, creating a channel evicts the least
+    /// recently subscribed channels without subscribers above this cap.
+    pub max_idle_channels: Option<usize>,
+
+    /// Allow the `DEBUG` subcommands, which expose server internals.
+    pub debug_commands: bool,
+
+    /// Dump at most this many bytes of a value with `DEBUG DUMPHEX`. `None`
+    /// dumps values in full.
+    pub debug_dump_limit: Option<usize>,
+
+    /// Record the last `command_history` commands received by the server,
+    /// returned by `DEBUG HISTORY` when `debug_commands` is set. `0` disables
+    /// recording.
+    ///
+    /// Unlike a slow log, every command is recorded, which helps post-mortem
+    /// debugging. Recording formats each command, so it is off by default.
+    pub command_history: usize,
+
+    /// Log a summary line at this interval: connected clients, number of
+    /// keys, commands per second, keyspace hit ratio and an estimate of the
+    /// memory used by keys and values. `None` disables the stats log.
+    pub stats_log_interval: Option<Duration>,
+
+    /// Transform values as they are stored and read back, e.g. to compress
+    /// or encrypt them at rest. `None` stores values as is.
+    pub value_codec: Option<Arc<dyn ValueCodec>>,
+
+    /// Number of tasks accepting connections from the listener. `0` and `1`
+    /// both run a single acceptor.
+    ///
+    /// On a multi-threaded runtime, several acceptors accept connections in
+    /// parallel, which helps workloads with a high connection churn. The
+    /// number of connections is still bounded by the server's limit.
+    pub acceptors: usize,
+
+    /// Maximum number of connections served at once. `None` uses
+    /// [`MAX_CONNECTIONS`](crate::consts::MAX_CONNECTIONS).
+    ///
+    /// Once the limit is reached, the server stops accepting connections until
+    /// an active connection terminates. Must be at least 1, see
+    /// [`validate`](ServerConfig::validate).
+    pub max_connections: Option<usize>,
+
+    /// How long to wait for active connections to terminate on shutdown.
+    /// `None` uses
+    /// [`DEFAULT_SHUTDOWN_TIMEOUT`](crate::consts::DEFAULT_SHUTDOWN_TIMEOUT).
+    ///
+    /// Shutdown completes as soon as all the connections terminated. A
+    /// connection can outlive the deadline, e.g. when blocked writing to a
+    /// client which does not read, in which case the server stops waiting.
+    pub shutdown_timeout: Option<Duration>,
+
+    /// Measure how long commands wait for the store lock, and count the waits
+    /// longer than this threshold as lock contentions, logging a warning.
+    /// `None` disables the measurement.
+    ///
+    /// Contentions are reported by the stats log. Frequent contentions mean
+    /// commands are serialized on the store, which adds latency.
+    pub lock_wait_threshold: Option<Duration>,
+
+    /// Abort the commands iterating the whole keyspace, `KEYS` and `SCAN`,
+    /// once they held the store lock for longer than this, replying with an
+    /// error. `None` lets them run to completion.
+    ///
+    /// These commands make a pass over every key under the store lock, which
+    /// stalls writers on a large keyspace. The clock is only checked
+    /// periodically, so a command may slightly overrun its budget.
+    pub command_budget: Option<Duration>,
+
+    /// Initial size of each connection's read buffer, which grows as needed.
+    /// `None` uses [`DEFAULT_READ_BUFFER_SIZE`](crate::consts::DEFAULT_READ_BUFFER_SIZE).
+    pub read_buffer_size: Option<usize>,
+
+    /// Size of each connection's write buffer. `None` uses
+    /// [`DEFAULT_WRITE_BUFFER_SIZE`](crate::consts::DEFAULT_WRITE_BUFFER_SIZE).
+    pub write_buffer_size: Option<usize>,
+
+    /// Notified when connections are accepted and when they terminate, e.g.
+    /// to feed a dashboard. `None` disables the notifications.
+    pub connection_observer: Option<Arc<dyn ConnectionObserver>>,
+}
+
+impl ServerConfig {
+    /// Check that the configuration can be served, as done by
+    /// [`run_with_config`](crate::server::run_with_config).
+    ///
+    /// Returns `MiniRedisServerError::InvalidConfig` if `max_connections` is
+    /// `Some(0)`, as no connection could ever be served.
+    pub fn validate(&self) -> Result<(), MiniRedisServerError> {
+        if self.max_connections == Some(0) {
+            return Err(MiniRedisServerError::InvalidConfig(
+                "max_connections must be at least 1".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A reversible transformation applied to values by the store.
+///
+/// Values are encoded when set, and decoded when read. Commands only ever see
+/// decoded values, so lengths and replies are those of the original value.
+/// Expirations are unaffected. Pub/sub messages are not stored, so they are
+/// not encoded.
+pub trait ValueCodec: Debug + Send + Sync {
+    /// Encode a value before it is stored.
+    fn encode(&self, value: Bytes) -> Bytes;
+
+    /// Decode a stored value before it is returned. Must reverse `encode`.
+    fn decode(&self, value: Bytes) -> Bytes;
+}
+
+/// Observes the lifecycle of the connections accepted by the server.
+///
+/// Callbacks are invoked from the connection's task, so they should return
+/// quickly. Connections are identified by the id also reported by
+/// `CLIENT LIST`.
+pub trait ConnectionObserver: Debug + Send + Sync {
+    /// A connection from `peer` was accepted.
+    fn on_connect(&self, _peer: SocketAddr, _id: u64) {}
+
+    /// The connection `id` terminated.
+    fn on_disconnect(&self, _id: u64, _reason: DisconnectReason) {}
+}
+
+/// Why a connection terminated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The peer closed the connection.
+    Closed,
+
+    /// The server is shutting down.
+    Shutdown,
+
+    /// The peer reset the connection, or closed it in the middle of a frame.
+    Reset,
+
+    /// The connection failed, e.g. because the peer sent an invalid frame.
+    /// Carries the error message.
+    Error(String),
+}
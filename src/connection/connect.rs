@@ -1,8 +1,10 @@
-use std::io::Cursor;
+use std::io::{self, Cursor};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use bytes::{Buf, BytesMut};
 use log::warn;
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter};
 use tokio::net::TcpStream;
 
 use crate::connection::frame::Frame;
@@ -20,31 +22,86 @@ use crate::error::{MiniRedisConnectionError, MiniRedisParseError};
 ///
 /// When sending frames, the frame is first encoded into the write buffer.
 /// The contents of the write buffer are then written to the socket.
+///
+/// The stream is generic so that tests can exercise the framing over an
+/// in-memory stream; it is always a `TcpStream` otherwise.
 #[derive(Debug)]
-pub struct Connection {
+pub struct Connection<S = TcpStream> {
     /// The `TcpStream`. It is decorated with a `BufWriter`, which provides write
     /// level buffering. The `BufWriter` implementation provided by Tokio is
     /// sufficient for our needs.
-    stream: BufWriter<TcpStream>,
+    stream: BufWriter<S>,
 
     // The buffer for reading frames.
     buffer: BytesMut,
+
+    /// Traffic counters of this connection.
+    stats: Arc<ConnectionStats>,
+
+    /// Set when writing a frame failed midway. The peer may have received
+    /// part of the frame, so any further frame written would be misread.
+    poisoned: bool,
 }
 
-impl Connection {
-    /// Create a new `Connection`, backed by `socket`. Read and write buffers
-    /// are initialized.
-    pub fn new(socket: TcpStream) -> Connection {
+/// Traffic counters of a `Connection`.
+///
+/// The counters are atomics so they can be shared, e.g. with the registry of
+/// connected clients, and read while the connection is in use.
+#[derive(Debug, Default)]
+pub struct ConnectionStats {
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+    frames_read: AtomicU64,
+    commands_applied: AtomicU64,
+}
+
+impl ConnectionStats {
+    /// Total number of bytes read from the socket.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Total number of bytes written to the socket.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Total number of frames parsed from the socket.
+    pub fn frames_read(&self) -> u64 {
+        self.frames_read.load(Ordering::Relaxed)
+    }
+
+    /// Total number of commands applied on the connection.
+    pub fn commands_applied(&self) -> u64 {
+        self.commands_applied.load(Ordering::Relaxed)
+    }
+
+    /// Record a command applied on the connection.
+    pub(crate) fn record_command(&self) {
+        self.commands_applied.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    /// Create a new `Connection`, backed by `socket`, with a read buffer of
+    /// `read_size` bytes and a write buffer of `write_size` bytes.
+    ///
+    /// The read buffer grows as needed to hold a frame, while values larger
+    /// than the write buffer bypass it.
+    pub fn with_capacity(socket: S, read_size: usize, write_size: usize) -> Connection<S> {
         Connection {
-            stream: BufWriter::new(socket),
-            // Default to a 4KB read buffer. For the use case of mini redis,
-            // this is fine. However, real applications will want to tune this
-            // value to their specific use case. There is a high likelihood that
-            // a larger read buffer will work better.
-            buffer: BytesMut::with_capacity(4 * 1024),
+            stream: BufWriter::with_capacity(write_size, socket),
+            buffer: BytesMut::with_capacity(read_size),
+            stats: Arc::new(ConnectionStats::default()),
+            poisoned: false,
         }
     }
 
+    /// Returns the traffic counters of this connection.
+    pub fn stats(&self) -> &Arc<ConnectionStats> {
+        &self.stats
+    }
+
     /// Read a single `Frame` value from the underlying stream.
     ///
     /// The function waits until it has retrieved enough data to parse a frame.
@@ -69,7 +126,10 @@ impl Connection {
             //
             // On success, the number of bytes is returned. `0` indicates "end
             // of stream".
-            if 0 == self.stream.read_buf(&mut self.buffer).await? {
+            let n = self.stream.read_buf(&mut self.buffer).await?;
+            self.stats.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+
+            if 0 == n {
                 // The remote closed the connection. For this to be a clean
                 // shutdown, there should be no data in the read buffer. If
                 // there is, this means that the peer closed the socket while
@@ -88,59 +148,82 @@ impl Connection {
     /// enough data has been buffered yet, `Ok(None)` is returned. If the
     /// buffered data does not represent a valid frame, `Err` is returned.
     fn parse_frame(&mut self) -> Result<Option<Frame>, MiniRedisConnectionError> {
-        // Cursor is used to track the "current" location in the
-        // buffer. Cursor also implements `Buf` from the `bytes` crate
-        // which provides a number of helpful utilities for working
-        // with bytes.
-        let mut buf = Cursor::new(&self.buffer[..]);
-
-        // The first step is to check if enough data has been buffered to parse a single frame.
-        // This step is usually much faster than doing a full
-        // parse of the frame, and allows us to skip allocating data structures
-        // to hold the frame data unless we know the full frame has been received.
-        match Frame::check(&mut buf) {
-            Ok(_) => {
-                // The `check` function will have advanced the cursor until the
-                // end of the frame. Since the cursor had position set to zero
-                // before `Frame::check` was called, we obtain the length of the
-                // frame by checking the cursor position.
-                let len = buf.position() as usize;
-
-                // Reset the position to zero before passing the cursor to
-                // `Frame::parse`.
-                buf.set_position(0);
-
-                // Parse the frame from the buffer. This allocates the necessary
-                // structures to represent the frame and returns the frame value.
-                //
-                // If the encoded frame representation is invalid, an error is
-                // returned. This should terminate the **current** connection
-                // but should not impact any other connected client.
-                let frame = Frame::parse(&mut buf)?;
+        loop {
+            // Cursor is used to track the "current" location in the
+            // buffer. Cursor also implements `Buf` from the `bytes` crate
+            // which provides a number of helpful utilities for working
+            // with bytes.
+            let mut buf = Cursor::new(&self.buffer[..]);
+
+            // Clients like `telnet` send commands as lines of text rather than as
+            // RESP arrays. Those are parsed as arrays of bulk strings.
+            let inline = Frame::is_inline(&self.buffer);
+
+            // The first step is to check if enough data has been buffered to parse a single frame.
+            // This step is usually much faster than doing a full
+            // parse of the frame, and allows us to skip allocating data structures
+            // to hold the frame data unless we know the full frame has been received.
+            let check = if inline {
+                Frame::check_inline(&mut buf)
+            } else {
+                Frame::check(&mut buf)
+            };
+            match check {
+                Ok(_) => {
+                    // The `check` function will have advanced the cursor until the
+                    // end of the frame. Since the cursor had position set to zero
+                    // before `Frame::check` was called, we obtain the length of the
+                    // frame by checking the cursor position.
+                    let len = buf.position() as usize;
 
-                // Discard the parsed data from the read buffer.
+                    // Reset the position to zero before passing the cursor to
+                    // `Frame::parse`.
+                    buf.set_position(0);
+
+                    // Parse the frame from the buffer. This allocates the necessary
+                    // structures to represent the frame and returns the frame value.
+                    //
+                    // If the encoded frame representation is invalid, an error is
+                    // returned. This should terminate the **current** connection
+                    // but should not impact any other connected client.
+                    let frame = if inline {
+                        Frame::parse_inline(&mut buf)?
+                    } else {
+                        Frame::parse(&mut buf)?
+                    };
+
+                    // Discard the parsed data from the read buffer.
+                    //
+                    // When `advance` is called on the read buffer, all of the data
+                    // up to `len` is discarded. The details of how this works is
+                    // left to `BytesMut`. This is often done by moving an internal
+                    // cursor, but it may be done by reallocating and copying data.
+                    self.buffer.advance(len);
+
+                    // Blank lines are skipped, as Redis does. This loops rather
+                    // than recursing, as a client may send any number of them.
+                    if matches!(&frame, Frame::Array(args) if inline && args.is_empty()) {
+                        continue;
+                    }
+
+                    self.stats.frames_read.fetch_add(1, Ordering::Relaxed);
+
+                    // Return the parsed frame to the caller.
+                    return Ok(Some(frame));
+                }
+                // There is not enough data present in the read buffer to parse a
+                // single frame. We must wait for more data to be received from the
+                // socket. Reading from the socket will be done in the statement
+                // after this `match`.
                 //
-                // When `advance` is called on the read buffer, all of the data
-                // up to `len` is discarded. The details of how this works is
-                // left to `BytesMut`. This is often done by moving an internal
-                // cursor, but it may be done by reallocating and copying data.
-                self.buffer.advance(len);
-
-                // Return the parsed frame to the caller.
-                Ok(Some(frame))
+                // We do not want to return `Err` from here as this "error" is an
+                // expected runtime condition.
+                Err(MiniRedisParseError::Incomplete) => return Ok(None),
+                // An error was encountered while parsing the frame. The connection
+                // is now in an invalid state. Returning `Err` from here will result
+                // in the connection being closed.
+                Err(e) => return Err(e.into()),
             }
-            // There is not enough data present in the read buffer to parse a
-            // single frame. We must wait for more data to be received from the
-            // socket. Reading from the socket will be done in the statement
-            // after this `match`.
-            //
-            // We do not want to return `Err` from here as this "error" is an
-            // expected runtime condition.
-            Err(MiniRedisParseError::Incomplete) => Ok(None),
-            // An error was encountered while parsing the frame. The connection
-            // is now in an invalid state. Returning `Err` from here will result
-            // in the connection being closed.
-            Err(e) => Err(e.into()),
         }
     }
 
@@ -152,27 +235,53 @@ impl Connection {
     /// syscalls. However, it is fine to call these functions on a *buffered*
     /// write stream. The data will be written to the buffer. Once the buffer is
     /// full, it is flushed to the underlying socket.
+    ///
+    /// Short writes are retried until the whole frame is written. If writing
+    /// fails midway, the connection is left unusable: the peer may have
+    /// received part of the frame, so every later write fails as well instead
+    /// of desynchronizing the stream.
     pub async fn write_frame(&mut self, frame: &Frame) -> Result<(), MiniRedisConnectionError> {
-        // Arrays are encoded by encoding each entry. All other frame types are
-        // considered literals. For now, mini-redis is not able to encode
-        // recursive frame structures. See below for more details.
-        match frame {
-            Frame::Array(val) => {
-                // Encode the frame type prefix. For an array, it is `*`.
-                self.stream.write_u8(b'*').await?;
-
-                // Encode the length of the array.
-                self.write_decimal(val.len() as u64).await?;
-
-                // Iterate and encode each entry in the array.
-                // todo ?
-                // for  in &**val {
-                for entry in val {
-                    self.write_value(entry).await?;
+        if self.poisoned {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "connection unusable after a failed write",
+            )
+            .into());
+        }
+
+        let res = self.write_frame_inner(frame).await;
+        if res.is_err() {
+            self.poisoned = true;
+        }
+        res
+    }
+
+    /// Encode `frame` to the stream and flush it, see `write_frame`.
+    async fn write_frame_inner(&mut self, frame: &Frame) -> Result<(), MiniRedisConnectionError> {
+        // Arrays are encoded by encoding their length, then each entry. All
+        // other frame types are considered literals. Async fns cannot recurse,
+        // so nested arrays are walked with an explicit stack of the entries
+        // remaining to encode at each level.
+        let mut stack = vec![std::slice::from_ref(frame).iter()];
+        while let Some(entries) = stack.last_mut() {
+            match entries.next() {
+                Some(Frame::Array(val)) => {
+                    // Encode the frame type prefix. For an array, it is `*`.
+                    self.write_bytes(b"*").await?;
+
+                    // Encode the length of the array.
+                    self.write_decimal(val.len() as i64).await?;
+
+                    // Encode the entries of the array before moving on.
+                    stack.push(val.iter());
+                }
+                // The frame type is a literal. Encode the value directly.
+                Some(frame) => self.write_value(frame).await?,
+                // This level is done, resume with the enclosing array.
+                None => {
+                    stack.pop();
                 }
             }
-            // The frame type is a literal. Encode the value directly.
-            _ => self.write_value(frame).await?,
         }
 
         // Ensure the encoded frame is written to the socket. The calls above
@@ -185,34 +294,31 @@ impl Connection {
     async fn write_value(&mut self, frame: &Frame) -> Result<(), MiniRedisConnectionError> {
         match frame {
             Frame::Simple(val) => {
-                self.stream.write_u8(b'+').await?;
-                self.stream.write_all(val.as_bytes()).await?;
-                self.stream.write_all(b"\r\n").await?;
+                self.write_bytes(b"+").await?;
+                self.write_bytes(val.as_bytes()).await?;
+                self.write_bytes(b"\r\n").await?;
             }
             Frame::Error(val) => {
-                self.stream.write_u8(b'-').await?;
-                self.stream.write_all(val.as_bytes()).await?;
-                self.stream.write_all(b"\r\n").await?;
+                self.write_bytes(b"-").await?;
+                self.write_bytes(val.as_bytes()).await?;
+                self.write_bytes(b"\r\n").await?;
             }
             Frame::Integer(val) => {
-                self.stream.write_u8(b':').await?;
+                self.write_bytes(b":").await?;
                 self.write_decimal(*val).await?;
             }
             Frame::Null => {
-                self.stream.write_all(b"$-1\r\n").await?;
+                self.write_bytes(b"$-1\r\n").await?;
             }
             Frame::Bulk(val) => {
                 let len = val.len();
 
-                self.stream.write_u8(b'$').await?;
-                self.write_decimal(len as u64).await?;
-                self.stream.write_all(val).await?;
-                self.stream.write_all(b"\r\n").await?;
+                self.write_bytes(b"$").await?;
+                self.write_decimal(len as i64).await?;
+                self.write_bytes(val).await?;
+                self.write_bytes(b"\r\n").await?;
             }
-            // Encoding an `Array` from within a value cannot be done using a
-            // recursive strategy. In general, async fns do not support
-            // recursion. Mini-redis has not needed to encode nested arrays yet,
-            // so for now it is skipped.
+            // Arrays, nested or not, are encoded by `write_frame_inner`.
             Frame::Array(_val) => {
                 warn!("unreachable code: recursive write_value: {:?}", _val);
                 return Err(MiniRedisParseError::Unimplemented.into());
@@ -222,8 +328,17 @@ impl Connection {
         Ok(())
     }
 
+    /// Write `src` to the buffered stream, accounting for it in the stats.
+    async fn write_bytes(&mut self, src: &[u8]) -> Result<(), MiniRedisConnectionError> {
+        self.stream.write_all(src).await?;
+        self.stats
+            .bytes_written
+            .fetch_add(src.len() as u64, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Write a decimal frame to the stream
-    async fn write_decimal(&mut self, val: u64) -> Result<(), MiniRedisConnectionError> {
+    async fn write_decimal(&mut self, val: i64) -> Result<(), MiniRedisConnectionError> {
         use std::io::Write;
 
         // Convert the value to a string
@@ -233,9 +348,187 @@ impl Connection {
         write!(&mut buf, "{}", val)?;
 
         let pos = buf.position() as usize;
-        self.stream.write_all(&buf.get_ref()[..pos]).await?;
-        self.stream.write_all(b"\r\n").await?;
+        self.write_bytes(&buf.get_ref()[..pos]).await?;
+        self.write_bytes(b"\r\n").await?;
 
         Ok(())
     }
 }
+
+impl Connection<TcpStream> {
+    /// Read a single `Frame` value if one is available, without waiting.
+    ///
+    /// Data already received by the socket is read without blocking. Any
+    /// data remaining in the read buffer after the frame has been parsed is
+    /// kept there for the next read.
+    ///
+    /// # Returns
+    ///
+    /// On success, the received frame is returned, or `None` if a full frame
+    /// has not been received yet. As there is no frame to report, the remote
+    /// closing the connection is always reported as an error.
+    pub fn try_read_frame(&mut self) -> Result<Option<Frame>, MiniRedisConnectionError> {
+        loop {
+            if let Some(frame) = self.parse_frame()? {
+                return Ok(Some(frame));
+            }
+
+            // Only reads are performed here, so bypassing the `BufWriter` is
+            // fine.
+            match self.stream.get_ref().try_read_buf(&mut self.buffer) {
+                Ok(0) => return Err(MiniRedisConnectionError::Disconnect),
+                Ok(n) => {
+                    self.stats.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+                }
+                // No data is available on the socket right now.
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use bytes::Bytes;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use crate::connection::connect::Connection;
+    use crate::connection::frame::Frame;
+    use crate::consts::{DEFAULT_READ_BUFFER_SIZE, DEFAULT_WRITE_BUFFER_SIZE};
+
+    /// A stream accepting at most `chunk` bytes per write, and not being
+    /// ready for every other write. Writes fail once `fail_after` bytes have
+    /// been accepted.
+    #[derive(Debug)]
+    struct ChunkedStream {
+        written: Vec<u8>,
+        chunk: usize,
+        fail_after: Option<usize>,
+        ready: bool,
+    }
+
+    impl ChunkedStream {
+        fn new(chunk: usize, fail_after: Option<usize>) -> ChunkedStream {
+            ChunkedStream {
+                written: vec![],
+                chunk,
+                fail_after,
+                ready: false,
+            }
+        }
+    }
+
+    impl AsyncRead for ChunkedStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for ChunkedStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+
+            // Would block: the task is woken up to try again.
+            this.ready = !this.ready;
+            if !this.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            if matches!(this.fail_after, Some(limit) if this.written.len() >= limit) {
+                return Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()));
+            }
+
+            let n = buf.len().min(this.chunk);
+            this.written.extend_from_slice(&buf[..n]);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn connection(stream: ChunkedStream) -> Connection<ChunkedStream> {
+        Connection::with_capacity(stream, DEFAULT_READ_BUFFER_SIZE, DEFAULT_WRITE_BUFFER_SIZE)
+    }
+
+    /// A frame is delivered intact over short writes, whether it goes through
+    /// the write buffer or, for large values, bypasses it.
+    #[tokio::test]
+    async fn write_frame_retries_short_writes() {
+        let mut connection = connection(ChunkedStream::new(3, None));
+
+        let large = Bytes::from(vec![b'x'; 10 * 1024]);
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from("hello")).unwrap();
+        frame.push_bulk(large.clone()).unwrap();
+        frame.push_int(42).unwrap();
+        connection.write_frame(&frame).await.unwrap();
+
+        let mut expected = b"*3\r\n$5\r\nhello\r\n$10240\r\n".to_vec();
+        expected.extend_from_slice(&large);
+        expected.extend_from_slice(b"\r\n:42\r\n");
+        assert_eq!(expected, connection.stream.get_ref().written);
+        assert_eq!(expected.len() as u64, connection.stats().bytes_written());
+    }
+
+    /// Once writing a frame failed midway, later writes fail instead of
+    /// appending to the partially written frame.
+    #[tokio::test]
+    async fn write_frame_failure_poisons_connection() {
+        let mut connection = connection(ChunkedStream::new(5, Some(10)));
+
+        let frame = Frame::Bulk(Bytes::from("hello world"));
+        assert!(connection.write_frame(&frame).await.is_err());
+        let written = connection.stream.get_ref().written.clone();
+        assert_eq!(10, written.len());
+
+        let frame = Frame::Simple("OK".into());
+        assert!(connection.write_frame(&frame).await.is_err());
+        assert_eq!(written, connection.stream.get_ref().written);
+    }
+
+    /// Nested arrays and negative integers are encoded, and parse back to the
+    /// same frame.
+    #[tokio::test]
+    async fn write_frame_nested_arrays() {
+        let mut connection = connection(ChunkedStream::new(64, None));
+
+        let frame = Frame::Array(vec![
+            Frame::Array(vec![
+                Frame::Bulk(Bytes::from("set")),
+                Frame::Integer(-3),
+                Frame::Array(vec![Frame::Simple("write".into())]),
+            ]),
+            Frame::Array(vec![]),
+            Frame::Null,
+        ]);
+        connection.write_frame(&frame).await.unwrap();
+
+        let written = &connection.stream.get_ref().written;
+        assert_eq!(
+            &b"*3\r\n*3\r\n$3\r\nset\r\n:-3\r\n*1\r\n+write\r\n*0\r\n$-1\r\n"[..],
+            &written[..]
+        );
+        let parsed = Frame::parse(&mut Cursor::new(&written[..])).unwrap();
+        assert_eq!(frame, parsed);
+    }
+}
@@ -10,14 +10,19 @@ use std::io::Cursor;
 
 use bytes::{Buf, Bytes};
 
+use crate::consts::MAX_INLINE_LEN;
 use crate::error::MiniRedisParseError;
 
 /// A frame in the Redis protocol.
-#[derive(Clone, Debug)]
+///
+/// Frames compare equal when they have the same variant and the same content,
+/// recursively for arrays. There is no floating point variant, so equality is
+/// total.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Frame {
     Simple(String),
     Error(String),
-    Integer(u64),
+    Integer(i64),
     Bulk(Bytes),
     Null,
     Array(Vec<Frame>),
@@ -50,8 +55,8 @@ impl fmt::Display for Frame {
                 for (i, part) in parts.iter().enumerate() {
                     if i > 0 {
                         write!(fmt, " ")?;
-                        part.fmt(fmt)?;
                     }
+                    part.fmt(fmt)?;
                 }
 
                 Ok(())
@@ -78,7 +83,7 @@ impl Frame {
     }
 
     /// Push an "integer" frame into the array. `self` must be an Array frame.
-    pub(crate) fn push_int(&mut self, value: u64) -> Result<(), MiniRedisParseError> {
+    pub(crate) fn push_int(&mut self, value: i64) -> Result<(), MiniRedisParseError> {
         match self {
             Frame::Array(vec) => {
                 vec.push(Frame::Integer(value));
@@ -103,7 +108,7 @@ impl Frame {
                 Ok(())
             }
             b':' => {
-                let _ = get_decimal(src)?;
+                let _ = get_integer(src)?;
                 Ok(())
             }
             b'$' => {
@@ -112,14 +117,14 @@ impl Frame {
                     skip(src, 4)
                 } else {
                     // Read the bulk string
-                    let len: usize = get_decimal(src)?.try_into()?;
+                    let len: usize = get_bulk_len(src)?.try_into()?;
 
                     // skip that number of bytes + 2 (\r\n).
                     skip(src, len + 2)
                 }
             }
             b'*' => {
-                let len = get_decimal(src)?;
+                let len = get_multibulk_len(src)?;
 
                 for _ in 0..len {
                     Frame::check(src)?;
@@ -134,6 +139,36 @@ impl Frame {
         }
     }
 
+    /// Returns `true` if `src` starts with an inline command rather than a
+    /// RESP frame, i.e. its first byte is not a frame type byte.
+    ///
+    /// Inline commands are what `telnet` or `nc` send: arguments separated by
+    /// spaces on a single line. Quoted arguments are not supported.
+    pub fn is_inline(src: &[u8]) -> bool {
+        !matches!(src.first(), None | Some(b'+' | b'-' | b':' | b'$' | b'*'))
+    }
+
+    /// Checks if an entire inline command can be decoded from `src`, see
+    /// `is_inline`.
+    pub fn check_inline(src: &mut Cursor<&[u8]>) -> Result<(), MiniRedisParseError> {
+        get_inline_line(src)?;
+        Ok(())
+    }
+
+    /// The inline command has already been validated with `check_inline`, so
+    /// parse it to an array of bulk strings, as if sent as a RESP array. A
+    /// blank line gives an empty array.
+    pub fn parse_inline(src: &mut Cursor<&[u8]>) -> Result<Frame, MiniRedisParseError> {
+        let line = get_inline_line(src)?;
+
+        Ok(Frame::Array(
+            line.split(|b| b.is_ascii_whitespace())
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| Frame::Bulk(Bytes::copy_from_slice(arg)))
+                .collect(),
+        ))
+    }
+
     /// The message has already been validated with `check`, so parse the bytes to Frame
     ///
     /// Redis serialization protocol (RESP) specification:
@@ -159,23 +194,21 @@ impl Frame {
                 Ok(Frame::Error(string))
             }
             b':' => {
-                let len = get_decimal(src)?;
-                Ok(Frame::Integer(len))
+                let value = get_integer(src)?;
+                Ok(Frame::Integer(value))
             }
             b'$' => {
                 if b'-' == peek_u8(src)? {
                     let line = get_line(src)?;
 
                     if line != b"-1" {
-                        return Err(MiniRedisParseError::Parse(
-                            "protocol error; invalid frame format".into(),
-                        ));
+                        return Err(MiniRedisParseError::InvalidBulkLength);
                     }
 
                     Ok(Frame::Null)
                 } else {
                     // Read the bulk string
-                    let len = get_decimal(src)?.try_into()?;
+                    let len = get_bulk_len(src)?.try_into()?;
                     let n = len + 2;
 
                     if src.remaining() < n {
@@ -191,7 +224,7 @@ impl Frame {
                 }
             }
             b'*' => {
-                let len = get_decimal(src)?.try_into()?;
+                let len = get_multibulk_len(src)?.try_into()?;
                 let mut out = Vec::with_capacity(len);
 
                 for _ in 0..len {
@@ -232,12 +265,63 @@ fn get_u8(src: &mut Cursor<&[u8]>) -> Result<u8, MiniRedisParseError> {
 
 /// Read a new-line terminated decimal
 fn get_decimal(src: &mut Cursor<&[u8]>) -> Result<u64, MiniRedisParseError> {
-    use atoi::atoi;
+    let line = get_line(src)?;
 
+    parse_decimal(line)
+        .ok_or_else(|| MiniRedisParseError::InvalidInteger(String::from_utf8_lossy(line).into()))
+}
+
+/// Read a new-line terminated signed decimal
+fn get_integer(src: &mut Cursor<&[u8]>) -> Result<i64, MiniRedisParseError> {
     let line = get_line(src)?;
 
-    atoi::<u64>(line).ok_or_else(|| {
-        MiniRedisParseError::Parse("protocol error; invalid frame format to get decimal".into())
+    parse_signed_decimal(line)
+        .ok_or_else(|| MiniRedisParseError::InvalidInteger(String::from_utf8_lossy(line).into()))
+}
+
+/// Parse `src` as a decimal. Unlike `atoi`, which stops at the first
+/// non-digit, all of `src` must be digits.
+pub(crate) fn parse_decimal(src: &[u8]) -> Option<u64> {
+    use atoi::FromRadix10Checked;
+
+    match u64::from_radix_10_checked(src) {
+        (Some(value), used) if used > 0 && used == src.len() => Some(value),
+        _ => None,
+    }
+}
+
+/// Parse `src` as a signed decimal, see `parse_decimal`.
+///
+/// As with Redis, only a `-` sign is accepted, and it must be followed by at
+/// least one digit.
+pub(crate) fn parse_signed_decimal(src: &[u8]) -> Option<i64> {
+    use atoi::FromRadix10SignedChecked;
+
+    // `atoi` accepts a `+` sign, and parses a lone sign as 0.
+    let digits = src.strip_prefix(b"-").unwrap_or(src);
+    if !digits.first().is_some_and(u8::is_ascii_digit) {
+        return None;
+    }
+
+    match i64::from_radix_10_signed_checked(src) {
+        (Some(value), used) if used > 0 && used == src.len() => Some(value),
+        _ => None,
+    }
+}
+
+/// Read the new-line terminated length of a bulk frame
+fn get_bulk_len(src: &mut Cursor<&[u8]>) -> Result<u64, MiniRedisParseError> {
+    get_decimal(src).map_err(|err| match err {
+        MiniRedisParseError::InvalidInteger(_) => MiniRedisParseError::InvalidBulkLength,
+        err => err,
+    })
+}
+
+/// Read the new-line terminated length of an array frame
+fn get_multibulk_len(src: &mut Cursor<&[u8]>) -> Result<u64, MiniRedisParseError> {
+    get_decimal(src).map_err(|err| match err {
+        MiniRedisParseError::InvalidInteger(_) => MiniRedisParseError::InvalidMultibulkLength,
+        err => err,
     })
 }
 
@@ -260,3 +344,174 @@ fn get_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], MiniRedisParseEr
 
     Err(MiniRedisParseError::Incomplete)
 }
+
+/// Find the line of an inline command.
+///
+/// Unlike `get_line`, the line may end with a bare `\n`, as sent by `nc`.
+fn get_inline_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], MiniRedisParseError> {
+    let start = src.position() as usize;
+    let rest = &src.get_ref()[start..];
+
+    match rest.iter().position(|&b| b == b'\n') {
+        Some(end) if end <= MAX_INLINE_LEN => {
+            src.set_position((start + end + 1) as u64);
+
+            let line = &rest[..end];
+            Ok(line.strip_suffix(b"\r").unwrap_or(line))
+        }
+        // Do not buffer an endless line.
+        _ if rest.len() > MAX_INLINE_LEN => Err(MiniRedisParseError::InlineTooBig),
+        _ => Err(MiniRedisParseError::Incomplete),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use bytes::Bytes;
+
+    use crate::connection::frame::{parse_signed_decimal, Frame};
+    use crate::consts::MAX_INLINE_LEN;
+    use crate::error::MiniRedisParseError;
+
+    fn check(src: &[u8]) -> Result<(), MiniRedisParseError> {
+        Frame::check(&mut Cursor::new(src))
+    }
+
+    #[test]
+    fn invalid_multibulk_length() {
+        assert!(matches!(
+            check(b"*x\r\n"),
+            Err(MiniRedisParseError::InvalidMultibulkLength)
+        ));
+        assert!(matches!(
+            check(b"*-2\r\n"),
+            Err(MiniRedisParseError::InvalidMultibulkLength)
+        ));
+    }
+
+    #[test]
+    fn invalid_bulk_length() {
+        assert!(matches!(
+            check(b"$abc\r\nhello\r\n"),
+            Err(MiniRedisParseError::InvalidBulkLength)
+        ));
+        assert!(matches!(
+            Frame::parse(&mut Cursor::new(&b"$-2\r\n"[..])),
+            Err(MiniRedisParseError::InvalidBulkLength)
+        ));
+    }
+
+    #[test]
+    fn invalid_integer() {
+        match check(b":12a\r\n") {
+            Err(MiniRedisParseError::InvalidInteger(value)) => assert_eq!("12a", value),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn invalid_signed_integer() {
+        for value in ["-", "+", "+5", "-+5", ""] {
+            assert_eq!(None, parse_signed_decimal(value.as_bytes()), "{:?}", value);
+        }
+        assert_eq!(Some(-5), parse_signed_decimal(b"-5"));
+        assert_eq!(
+            Some(i64::MIN),
+            parse_signed_decimal(b"-9223372036854775808")
+        );
+
+        match check(b":-\r\n") {
+            Err(MiniRedisParseError::InvalidInteger(value)) => assert_eq!("-", value),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn incomplete_is_not_an_invalid_length() {
+        assert!(matches!(
+            check(b"*2\r\n$3\r\nGE"),
+            Err(MiniRedisParseError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn nested_arrays_equality() {
+        let frame = |last: i64| {
+            Frame::Array(vec![
+                Frame::Bulk("message".into()),
+                Frame::Array(vec![Frame::Simple("OK".into()), Frame::Integer(last)]),
+                Frame::Null,
+            ])
+        };
+
+        assert_eq!(frame(1), frame(1));
+        assert_ne!(frame(1), frame(2));
+        assert_ne!(
+            Frame::Simple("OK".into()),
+            Frame::Bulk(Bytes::from_static(b"OK"))
+        );
+        assert_ne!(Frame::Array(vec![]), Frame::Null);
+    }
+
+    fn parse_inline(src: &[u8]) -> Result<Frame, MiniRedisParseError> {
+        let mut src = Cursor::new(src);
+        Frame::check_inline(&mut src)?;
+        src.set_position(0);
+        Frame::parse_inline(&mut src)
+    }
+
+    #[test]
+    fn inline_commands() {
+        let args = |args: &[&'static str]| {
+            Frame::Array(
+                args.iter()
+                    .map(|arg| Frame::Bulk(Bytes::from_static(arg.as_bytes())))
+                    .collect(),
+            )
+        };
+
+        assert!(Frame::is_inline(b"PING\r\n"));
+        assert!(!Frame::is_inline(b"*1\r\n$4\r\nPING\r\n"));
+        assert!(!Frame::is_inline(b""));
+
+        assert_eq!(args(&["PING"]), parse_inline(b"PING\r\n").unwrap());
+        assert_eq!(args(&["get", "foo"]), parse_inline(b"get foo\n").unwrap());
+        assert_eq!(
+            args(&["set", "foo", "bar"]),
+            parse_inline(b"  set\tfoo   bar \r\n").unwrap()
+        );
+        assert_eq!(args(&[]), parse_inline(b"\r\n").unwrap());
+    }
+
+    #[test]
+    fn inline_commands_incomplete_or_too_big() {
+        assert!(matches!(
+            parse_inline(b"get foo"),
+            Err(MiniRedisParseError::Incomplete)
+        ));
+
+        let long = vec![b'a'; MAX_INLINE_LEN + 1];
+        assert!(matches!(
+            parse_inline(&long),
+            Err(MiniRedisParseError::InlineTooBig)
+        ));
+    }
+
+    #[test]
+    fn display_arrays() {
+        let frame = Frame::Array(vec![
+            Frame::Bulk("subscribe".into()),
+            Frame::Bulk("foo".into()),
+            Frame::Integer(1),
+        ]);
+        assert_eq!("subscribe foo 1", frame.to_string());
+
+        assert_eq!("", Frame::Array(vec![]).to_string());
+        assert_eq!(
+            "OK",
+            Frame::Array(vec![Frame::Simple("OK".into())]).to_string()
+        );
+    }
+}
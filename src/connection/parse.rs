@@ -1,8 +1,9 @@
+use std::convert::TryFrom;
 use std::vec;
 
 use bytes::Bytes;
 
-use crate::connection::frame::Frame;
+use crate::connection::frame::{parse_decimal, parse_signed_decimal, Frame};
 use crate::error::MiniRedisParseError;
 
 /// Utility for parsing a command
@@ -24,12 +25,7 @@ impl Parse {
     pub(crate) fn new(frame: Frame) -> Result<Parse, MiniRedisParseError> {
         let array = match frame {
             Frame::Array(array) => array,
-            frame => {
-                return Err(MiniRedisParseError::Parse(format!(
-                    "protocol error; expected array, got {:?}",
-                    frame
-                )))
-            }
+            frame => return Err(MiniRedisParseError::ExpectedArray(format!("{:?}", frame))),
         };
 
         Ok(Parse {
@@ -37,6 +33,11 @@ impl Parse {
         })
     }
 
+    /// Returns the number of entries not consumed yet.
+    pub(crate) fn remaining(&self) -> usize {
+        self.parts.len()
+    }
+
     /// Return the next entry. Array frames are arrays of frames, so the next
     /// entry is a frame.
     fn next(&mut self) -> Result<Frame, MiniRedisParseError> {
@@ -56,9 +57,9 @@ impl Parse {
             Frame::Simple(s) => Ok(s),
             Frame::Bulk(data) => std::str::from_utf8(&data[..])
                 .map(|s| s.to_string())
-                .map_err(|_| MiniRedisParseError::Parse("protocol error; invalid string".into())),
-            frame => Err(MiniRedisParseError::Parse(format!(
-                "protocol error; expected simple frame or bulk frame, got {:?}",
+                .map_err(|err| MiniRedisParseError::InvalidString(err.to_string())),
+            frame => Err(MiniRedisParseError::InvalidString(format!(
+                "expected simple frame or bulk frame, got {:?}",
                 frame
             ))),
         }
@@ -91,21 +92,33 @@ impl Parse {
     /// If the next entry cannot be represented as an integer, then an error is
     /// returned.
     pub(crate) fn next_int(&mut self) -> Result<u64, MiniRedisParseError> {
-        use atoi::atoi;
-
         match self.next()? {
             // An integer frame type is already stored as an integer.
-            Frame::Integer(v) => Ok(v),
+            Frame::Integer(v) => {
+                u64::try_from(v).map_err(|_| MiniRedisParseError::InvalidInteger(v.to_string()))
+            }
             // Simple and bulk frames must be parsed as integers. If the parsing
             // fails, an error is returned.
-            Frame::Simple(data) => atoi::<u64>(data.as_bytes())
-                .ok_or_else(|| MiniRedisParseError::Parse("protocol error; invalid number".into())),
-            Frame::Bulk(data) => atoi::<u64>(&data)
-                .ok_or_else(|| MiniRedisParseError::Parse("protocol error; invalid number".into())),
-            frame => Err(MiniRedisParseError::Parse(format!(
-                "protocol error; expected int frame but got {:?}",
-                frame
-            ))),
+            Frame::Simple(data) => {
+                parse_decimal(data.as_bytes()).ok_or(MiniRedisParseError::InvalidInteger(data))
+            }
+            Frame::Bulk(data) => parse_decimal(&data).ok_or_else(|| {
+                MiniRedisParseError::InvalidInteger(String::from_utf8_lossy(&data).into())
+            }),
+            frame => Err(MiniRedisParseError::InvalidInteger(format!("{:?}", frame))),
+        }
+    }
+
+    /// Return the next entry as a signed integer, see `next_int`.
+    pub(crate) fn next_signed_int(&mut self) -> Result<i64, MiniRedisParseError> {
+        match self.next()? {
+            Frame::Integer(v) => Ok(v),
+            Frame::Simple(data) => parse_signed_decimal(data.as_bytes())
+                .ok_or(MiniRedisParseError::InvalidInteger(data)),
+            Frame::Bulk(data) => parse_signed_decimal(&data).ok_or_else(|| {
+                MiniRedisParseError::InvalidInteger(String::from_utf8_lossy(&data).into())
+            }),
+            frame => Err(MiniRedisParseError::InvalidInteger(format!("{:?}", frame))),
         }
     }
 
@@ -120,3 +133,41 @@ impl Parse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::connection::frame::Frame;
+    use crate::connection::parse::Parse;
+    use crate::error::MiniRedisParseError;
+
+    #[test]
+    fn expected_array() {
+        assert!(matches!(
+            Parse::new(Frame::Simple("GET".into())),
+            Err(MiniRedisParseError::ExpectedArray(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_string() {
+        let mut parse = Parse::new(Frame::Array(vec![Frame::Bulk(Bytes::from_static(&[
+            0xff, 0xfe,
+        ]))]))
+        .unwrap();
+        assert!(matches!(
+            parse.next_string(),
+            Err(MiniRedisParseError::InvalidString(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_integer() {
+        let mut parse = Parse::new(Frame::Array(vec![Frame::Bulk("ten".into())])).unwrap();
+        match parse.next_int() {
+            Err(MiniRedisParseError::InvalidInteger(value)) => assert_eq!("ten", value),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
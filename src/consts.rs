@@ -1,10 +1,31 @@
+use std::time::Duration;
+
 /// Default port that a redis server listens on.
 ///
 /// Used if no port is specified.
 pub const DEFAULT_PORT: u16 = 6379;
 
-/// Maximum number of concurrent connections the redis server will accept.
+/// Default maximum number of concurrent connections the redis server will
+/// accept, see `ServerConfig::max_connections`.
 ///
 /// When this limit is reached, the server will stop accepting connections until
 /// an active connection terminates.
 pub const MAX_CONNECTIONS: usize = 1024;
+
+/// Default initial size of a connection's read buffer.
+///
+/// For the use case of mini redis, this is fine. However, real applications
+/// will want to tune this value to their specific use case. There is a high
+/// likelihood that a larger read buffer will work better.
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 4 * 1024;
+
+/// Default size of a connection's write buffer.
+pub const DEFAULT_WRITE_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Maximum length of an inline command, i.e. a command sent as a line of
+/// text rather than as a RESP array. Same as Redis.
+pub const MAX_INLINE_LEN: usize = 64 * 1024;
+
+/// Default time given to active connections to terminate when the server
+/// shuts down, see `ServerConfig::shutdown_timeout`.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
@@ -12,6 +12,10 @@ pub enum MiniRedisServerError {
 
     #[error(transparent)]
     Parse(#[from] MiniRedisParseError),
+
+    /// The server configuration is invalid. Carries the reason.
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
 }
 
 #[derive(Error, Debug)]
@@ -25,13 +29,45 @@ pub enum MiniRedisClientError {
 
 /// Error encountered while parsing a frame.
 ///
-/// Only `EndOfStream` errors are handled at runtime. All other errors result in
-/// the connection being terminated.
+/// Errors encountered while reading a frame from the socket are protocol
+/// faults: the client is notified and the connection is terminated. Errors
+/// encountered while parsing a command out of a valid frame are reported to
+/// the client, and the connection keeps being served.
 #[derive(Error, Debug)]
 pub enum MiniRedisParseError {
-    #[error("invalid message encoding, parse failed")]
+    #[error("{0}")]
     Parse(String),
 
+    /// The length of an array frame is not a valid non-negative integer.
+    #[error("Protocol error: invalid multibulk length")]
+    InvalidMultibulkLength,
+
+    /// The length of a bulk frame is not a valid non-negative integer.
+    #[error("Protocol error: invalid bulk length")]
+    InvalidBulkLength,
+
+    /// An inline command is longer than
+    /// [`MAX_INLINE_LEN`](crate::consts::MAX_INLINE_LEN).
+    #[error("Protocol error: too big inline request")]
+    InlineTooBig,
+
+    /// A command was not sent as an array frame. Carries the received frame.
+    #[error("Protocol error: expected array, got {0}")]
+    ExpectedArray(String),
+
+    /// An integer was expected. Carries the received value.
+    #[error("value is not an integer or out of range")]
+    InvalidInteger(String),
+
+    /// A string was expected. Carries the reason it is invalid.
+    #[error("invalid string: {0}")]
+    InvalidString(String),
+
+    /// A command was sent with a wrong number of arguments. Carries the
+    /// command name.
+    #[error("wrong number of arguments for '{0}' command")]
+    WrongArity(String),
+
     /// Attempting to extract a value failed due to the frame being fully
     /// consumed.
     #[error("protocol error; unexpected end of stream")]
@@ -66,9 +102,50 @@ pub enum MiniRedisConnectionError {
     #[error("command execute error")]
     CommandExecute(String),
 
+    /// The server replied with an error. Carries the message, which usually
+    /// starts with an upper case error code such as `ERR` or `WRONGTYPE`.
+    #[error("error reply: {0}")]
+    ErrorReply(String),
+
     #[error("received next message failed, invalid frame type")]
     InvalidFrameType,
 
     #[error("invalid argument")]
     InvalidArgument(String),
 }
+
+impl MiniRedisConnectionError {
+    /// Returns the code of an error reply, e.g. `WRONGTYPE` for
+    /// `WRONGTYPE Operation against a key holding the wrong kind of value`.
+    ///
+    /// Returns `None` if this is not an error reply, or if the message does
+    /// not start with an upper case code. Matching on the code rather than on
+    /// the whole message is robust to message changes.
+    pub fn error_code(&self) -> Option<&str> {
+        let msg = match self {
+            MiniRedisConnectionError::ErrorReply(msg) => msg,
+            _ => return None,
+        };
+
+        let code = msg.split(' ').next()?;
+        let is_code = !code.is_empty()
+            && code
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_');
+        if is_code {
+            Some(code)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` for a `WRONGTYPE` error reply.
+    pub fn is_wrongtype(&self) -> bool {
+        self.error_code() == Some("WRONGTYPE")
+    }
+
+    /// Returns `true` for a `NOAUTH` error reply.
+    pub fn is_noauth(&self) -> bool {
+        self.error_code() == Some("NOAUTH")
+    }
+}
@@ -1,11 +1,39 @@
 use std::env;
+use std::sync::OnceLock;
 
 use log::{Level, LevelFilter, Metadata, Record};
+use tokio::sync::broadcast;
 
 use crate::config::LOG_LEVEL;
 
 struct Logger;
 
+/// A log record, as sent to log tails.
+#[derive(Debug, Clone)]
+pub(crate) struct TailRecord {
+    pub(crate) level: Level,
+
+    /// The target and the message of the record.
+    pub(crate) line: String,
+}
+
+/// Number of records buffered for each log tail. A tail lagging further
+/// behind misses records.
+const TAIL_CAPACITY: usize = 1024;
+
+/// Channel the records are sent to when log tails are attached.
+fn tail_sender() -> &'static broadcast::Sender<TailRecord> {
+    static TAIL: OnceLock<broadcast::Sender<TailRecord>> = OnceLock::new();
+    TAIL.get_or_init(|| broadcast::channel(TAIL_CAPACITY).0)
+}
+
+/// Attach a log tail, receiving every record logged from now on.
+///
+/// Records are only received if the logger was installed with [`init`].
+pub(crate) fn tail() -> broadcast::Receiver<TailRecord> {
+    tail_sender().subscribe()
+}
+
 pub fn init() {
     static LOGGER: Logger = Logger;
     log::set_logger(&LOGGER).unwrap();
@@ -46,6 +74,15 @@ impl log::Log for Logger {
             record.target(),
             record.args(),
         );
+
+        // Formatting the record is skipped unless a tail is attached.
+        let tail = tail_sender();
+        if tail.receiver_count() > 0 {
+            let _ = tail.send(TailRecord {
+                level: record.level(),
+                line: format!("{} - {}", record.target(), record.args()),
+            });
+        }
     }
 
     fn flush(&self) {}
@@ -1,8 +1,15 @@
+use std::io;
+use std::net::SocketAddr;
+
 use log::debug;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 
 use crate::cmd::Command;
+use crate::config::DisconnectReason;
 use crate::connection::connect::Connection;
+use crate::connection::frame::Frame;
+use crate::consts::{DEFAULT_READ_BUFFER_SIZE, DEFAULT_WRITE_BUFFER_SIZE};
 use crate::error::MiniRedisConnectionError;
 use crate::server::shutdown::Shutdown;
 use crate::storage::db::Db;
@@ -18,13 +25,23 @@ pub(crate) struct Handler {
     /// will need to interact with `db` in order to complete the work.
     pub(crate) db: Db,
 
+    /// Id of this connection in the registry of connected clients.
+    ///
+    /// The connection is registered when the `Handler` is created and
+    /// deregistered when it is dropped.
+    pub(crate) id: u64,
+
+    /// Address of the client.
+    pub(crate) addr: SocketAddr,
+
     /// The TCP connection decorated with the redis protocol encoder / decoder
     /// implemented using a buffered `TcpStream`.
     ///
     /// When `Listener` receives an inbound connection, the `TcpStream` is
-    /// passed to `Connection::new`, which initializes the associated buffers.
-    /// `Connection` allows the handler to operate at the "frame" level and keep
-    /// the byte level protocol parsing details encapsulated in `Connection`.
+    /// passed to `Connection::with_capacity`, which initializes the associated
+    /// buffers. `Connection` allows the handler to operate at the "frame" level
+    /// and keep the byte level protocol parsing details encapsulated in
+    /// `Connection`.
     pub(crate) connection: Connection,
 
     /// Listen for shutdown notifications.
@@ -42,6 +59,47 @@ pub(crate) struct Handler {
 }
 
 impl Handler {
+    /// Create the per-connection handler state for `socket` and register the
+    /// connection in the shared state.
+    pub(crate) fn new(
+        db: Db,
+        socket: TcpStream,
+        addr: SocketAddr,
+        shutdown: Shutdown,
+        shutdown_complete: mpsc::Sender<()>,
+    ) -> Handler {
+        // Initialize the connection state. This allocates read/write buffers
+        // to perform redis protocol frame parsing.
+        let config = db.config();
+        let connection = Connection::with_capacity(
+            socket,
+            config.read_buffer_size.unwrap_or(DEFAULT_READ_BUFFER_SIZE),
+            config
+                .write_buffer_size
+                .unwrap_or(DEFAULT_WRITE_BUFFER_SIZE),
+        );
+
+        let id = db.register_client(addr, connection.stats().clone());
+        if let Some(observer) = &db.config().connection_observer {
+            observer.on_connect(addr, id);
+        }
+        debug!(
+            "client connected: (id={}, addr={}, clients={})",
+            id,
+            addr,
+            db.num_clients()
+        );
+
+        Handler {
+            db,
+            id,
+            addr,
+            connection,
+            shutdown,
+            _shutdown_complete: shutdown_complete,
+        }
+    }
+
     /// Process a single connection.
     ///
     /// Request frames are read from the socket and processed. Responses are
@@ -55,13 +113,52 @@ impl Handler {
     /// When the shutdown signal is received, the connection is processed until
     /// it reaches a safe state, at which point it is terminated.
     pub(crate) async fn run(&mut self) -> Result<(), MiniRedisConnectionError> {
+        let res = self.serve().await;
+
+        if let Some(observer) = &self.db.config().connection_observer {
+            let reason = match &res {
+                Ok(()) if self.shutdown.is_shutdown() => DisconnectReason::Shutdown,
+                Ok(()) => DisconnectReason::Closed,
+                Err(MiniRedisConnectionError::Disconnect) => DisconnectReason::Reset,
+                Err(MiniRedisConnectionError::IoError(err))
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::ConnectionReset
+                            | io::ErrorKind::ConnectionAborted
+                            | io::ErrorKind::BrokenPipe
+                            | io::ErrorKind::UnexpectedEof
+                    ) =>
+                {
+                    DisconnectReason::Reset
+                }
+                Err(err) => DisconnectReason::Error(err.to_string()),
+            };
+            observer.on_disconnect(self.id, reason);
+        }
+
+        res
+    }
+
+    /// Serve the connection's requests until it terminates, see `run`.
+    async fn serve(&mut self) -> Result<(), MiniRedisConnectionError> {
         // As long as the shutdown signal has not been received, try to read a
         // new request frame.
         while !self.shutdown.is_shutdown() {
             // While reading a request frame, also listen for the shutdown
             // signal.
             let maybe_frame = tokio::select! {
-                res = self.connection.read_frame() => res?,
+                res = self.connection.read_frame() => match res {
+                    Ok(maybe_frame) => maybe_frame,
+                    // The client sent bytes which are not a valid frame. The
+                    // stream cannot be resynchronized, so the client is told
+                    // why before the connection is terminated.
+                    Err(MiniRedisConnectionError::ParseFrame(err)) => {
+                        let response = Frame::Error(format!("ERR {}", err));
+                        self.connection.write_frame(&response).await?;
+                        return Err(err.into());
+                    }
+                    Err(err) => return Err(err),
+                },
                 _ = self.shutdown.recv() => {
                     // If a shutdown signal is received, return from `run`.
                     // This will result in the task terminating.
@@ -80,10 +177,21 @@ impl Handler {
                 }
             };
 
+            self.db.record_command(self.addr, &frame);
+
             // Convert the redis frame into a command struct. This returns an
-            // error if the frame is not a valid redis command or it is an
-            // unsupported command.
-            let cmd = Command::from_frame(frame)?;
+            // error if the frame is not a valid redis command. The frame itself
+            // was well-formed, so the error is reported to the client and the
+            // connection keeps being served.
+            let cmd = match Command::from_frame(frame) {
+                Ok(cmd) => cmd,
+                Err(err) => {
+                    debug!("invalid command: {}", err);
+                    let response = Frame::Error(format!("ERR {}", err));
+                    self.connection.write_frame(&response).await?;
+                    continue;
+                }
+            };
 
             // Logs the `cmd` object.
             debug!("received command: {:?}", cmd);
@@ -97,8 +205,76 @@ impl Handler {
             // peer.
             cmd.apply(&self.db, &mut self.connection, &mut self.shutdown)
                 .await?;
+
+            self.connection.stats().record_command();
+
+            // Give other connections a chance to run when this one is busy
+            // processing a long pipeline.
+            let applied = self.connection.stats().commands_applied();
+            let yield_every = self.db.config().yield_every;
+            if yield_every > 0 && applied.is_multiple_of(yield_every) {
+                tokio::task::yield_now().await;
+            }
         }
 
         Ok(())
     }
 }
+
+impl Drop for Handler {
+    fn drop(&mut self) {
+        // However the connection terminated, remove it from the shared state
+        // so registries don't leak entries as connections churn.
+        if let Some(info) = self.db.deregister_client(self.id) {
+            debug!(
+                "client disconnected: (id={}, addr={}, clients={})",
+                self.id,
+                info.addr,
+                self.db.num_clients()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio::sync::{broadcast, mpsc};
+
+    use crate::config::ServerConfig;
+    use crate::server::handler::Handler;
+    use crate::server::shutdown::Shutdown;
+    use crate::storage::db::DbDropGuard;
+
+    /// A client that connects and then disconnects must not be left behind in
+    /// the shared registry of connected clients.
+    #[tokio::test]
+    async fn deregister_client_on_disconnect() {
+        let db_holder = DbDropGuard::new(ServerConfig::default());
+        let db = db_holder.db();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (socket, addr) = listener.accept().await.unwrap();
+
+        let (notify_shutdown, _) = broadcast::channel(1);
+        let (shutdown_complete_tx, _shutdown_complete_rx) = mpsc::channel(1);
+        let mut handler = Handler::new(
+            db.clone(),
+            socket,
+            addr,
+            Shutdown::new(notify_shutdown.subscribe()),
+            shutdown_complete_tx,
+        );
+        assert_eq!(1, db.num_clients());
+
+        // The peer disconnects, so the handler runs to completion.
+        drop(client);
+        handler.run().await.unwrap();
+        drop(handler);
+
+        assert_eq!(0, db.num_clients());
+    }
+}
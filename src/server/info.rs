@@ -0,0 +1,77 @@
+//! Information about the running server instance
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::time::{Duration, Instant};
+
+/// Seconds in a day, for `uptime_in_days`.
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Tracks when the server was started.
+///
+/// Captured once when the server starts, and shared with every connection
+/// through the `Db`.
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    /// Monotonic instant at which the server started, used to compute the
+    /// uptime.
+    started_at: Instant,
+
+    /// Wall-clock time at which the server started, in seconds since the
+    /// Unix epoch.
+    start_unix: u64,
+}
+
+impl ServerInfo {
+    /// Capture the current instant as the server start time.
+    pub(crate) fn new() -> ServerInfo {
+        ServerInfo {
+            started_at: Instant::now(),
+            start_unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Returns the wall-clock start time, in seconds since the Unix epoch.
+    pub fn start_unix(&self) -> u64 {
+        self.start_unix
+    }
+
+    /// Returns the time elapsed since the server started.
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Returns the number of whole seconds elapsed since the server started.
+    pub fn uptime_in_seconds(&self) -> u64 {
+        self.uptime().as_secs()
+    }
+
+    /// Returns the number of whole days elapsed since the server started.
+    pub fn uptime_in_days(&self) -> u64 {
+        self.uptime_in_seconds() / SECS_PER_DAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time::{self, Duration};
+
+    use crate::server::info::ServerInfo;
+
+    /// The uptime increases as time goes by.
+    #[tokio::test(start_paused = true)]
+    async fn uptime_increases() {
+        let info = ServerInfo::new();
+        assert_eq!(0, info.uptime_in_seconds());
+
+        time::sleep(Duration::from_secs(3)).await;
+        assert_eq!(3, info.uptime_in_seconds());
+        assert_eq!(0, info.uptime_in_days());
+
+        time::sleep(Duration::from_secs(2 * 24 * 60 * 60)).await;
+        assert_eq!(2, info.uptime_in_days());
+        assert!(info.start_unix() > 0);
+    }
+}
@@ -1,16 +1,18 @@
+use std::io;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use log::{error, info};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, mpsc, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time;
 
-use crate::connection::connect::Connection;
 use crate::error::MiniRedisConnectionError;
 use crate::server::handler::Handler;
 use crate::server::shutdown::Shutdown;
-use crate::storage::db::DbDropGuard;
+use crate::storage::db::{Db, DbDropGuard};
 
 /// Server listener state. Created in the `run` call. It includes a `run` method
 /// which performs the TCP listening and initialization of per-connection state.
@@ -25,8 +27,8 @@ pub(crate) struct Listener {
     /// retrieved and passed into the per connection state (`Handler`).
     pub(crate) db_holder: DbDropGuard,
 
-    /// TCP listener supplied by the `run` caller.
-    pub(crate) listener: TcpListener,
+    /// TCP listener supplied by the `run` caller, shared by the acceptors.
+    pub(crate) listener: Arc<TcpListener>,
 
     /// Limit the max number of connections.
     ///
@@ -83,6 +85,52 @@ impl Listener {
     pub(crate) async fn run(&mut self) -> Result<(), MiniRedisConnectionError> {
         info!("server started, accepting inbound connections");
 
+        // `0` is treated as a single acceptor.
+        let acceptors = self.db_holder.db().config().acceptors.max(1);
+
+        // The extra acceptors run on their own tasks, so that connections are
+        // accepted in parallel on a multi-threaded runtime. They are aborted
+        // when the `JoinSet` is dropped, i.e. when `run` is dropped on
+        // shutdown.
+        let mut tasks = JoinSet::new();
+        for _ in 1..acceptors {
+            tasks.spawn(self.acceptor().run());
+        }
+
+        // The current task is the first acceptor. The first acceptor giving
+        // up stops the server.
+        tokio::select! {
+            res = self.acceptor().run() => res,
+            Some(res) = tasks.join_next() => res.map_err(io::Error::from)?,
+        }
+    }
+
+    /// Returns the state of a new accept loop, sharing the listener, the
+    /// connection limit and the shutdown channels.
+    fn acceptor(&self) -> Acceptor {
+        Acceptor {
+            db: self.db_holder.db(),
+            listener: self.listener.clone(),
+            limit_connections: self.limit_connections.clone(),
+            notify_shutdown: self.notify_shutdown.clone(),
+            shutdown_complete_tx: self.shutdown_complete_tx.clone(),
+        }
+    }
+}
+
+/// State of a single accept loop. See [`Listener`] for the fields.
+#[derive(Debug)]
+struct Acceptor {
+    db: Db,
+    listener: Arc<TcpListener>,
+    limit_connections: Arc<Semaphore>,
+    notify_shutdown: broadcast::Sender<()>,
+    shutdown_complete_tx: mpsc::Sender<()>,
+}
+
+impl Acceptor {
+    /// Accept inbound connections, spawning a task to process each of them.
+    async fn run(self) -> Result<(), MiniRedisConnectionError> {
         loop {
             // Wait for a permit to become available
             //
@@ -102,23 +150,19 @@ impl Listener {
             // Accept a new socket. This will attempt to perform error handling.
             // The `accept` method internally attempts to recover errors, so an
             // error here is non-recoverable.
-            let socket = self.accept().await?;
+            let (socket, addr) = self.accept().await?;
 
             // Create the necessary per-connection handler state.
-            let mut handler = Handler {
+            let mut handler = Handler::new(
                 // Get a handle to the shared database.
-                db: self.db_holder.db(),
-
-                // Initialize the connection state. This allocates read/write
-                // buffers to perform redis protocol frame parsing.
-                connection: Connection::new(socket),
-
+                self.db.clone(),
+                socket,
+                addr,
                 // Receive shutdown notifications.
-                shutdown: Shutdown::new(self.notify_shutdown.subscribe()),
-
+                Shutdown::new(self.notify_shutdown.subscribe()),
                 // Notifies the receiver half once all clones are dropped.
-                _shutdown_complete: self.shutdown_complete_tx.clone(),
-            };
+                self.shutdown_complete_tx.clone(),
+            );
 
             // Spawn a new task to process the connections. Tokio tasks are like
             // asynchronous green threads and are executed concurrently.
@@ -139,33 +183,65 @@ impl Listener {
     /// Errors are handled by backing off and retrying. An exponential backoff
     /// strategy is used. After the first failure, the task waits for 1 second.
     /// After the second failure, the task waits for 2 seconds. Each subsequent
-    /// failure doubles the wait time. If accepting fails on the 6th try after
-    /// waiting for 64 seconds, then this function returns with an error.
-    async fn accept(&mut self) -> Result<TcpStream, MiniRedisConnectionError> {
-        let mut backoff = 1;
+    /// failure doubles the wait time. If accepting fails again after waiting
+    /// for 64 seconds, then this function returns with an error.
+    async fn accept(&self) -> Result<(TcpStream, SocketAddr), MiniRedisConnectionError> {
+        let mut failures = 0;
 
         // Try to accept a few times
         loop {
             // Perform the accept operation. If a socket is successfully
             // accepted, return it. Otherwise, save the error.
-            match self.listener.accept().await {
-                Ok((socket, _)) => return Ok(socket),
+            let backoff = match self.listener.accept().await {
+                Ok((socket, addr)) => return Ok((socket, addr)),
                 Err(err) => {
-                    if backoff > 64 {
-                        // Accept has failed too many times. Return the error.
-                        error!("failed to accept socket after retry: {}", err);
-                        return Err(err.into());
-                    } else {
-                        error!("failed to accept socket: {}", err);
+                    failures += 1;
+                    match accept_backoff(failures) {
+                        Some(backoff) => {
+                            error!("failed to accept socket: {}", err);
+                            backoff
+                        }
+                        None => {
+                            // Accept has failed too many times. Return the error.
+                            error!("failed to accept socket after retry: {}", err);
+                            return Err(err.into());
+                        }
                     }
                 }
-            }
+            };
 
             // Pause execution until the back off period elapses.
-            time::sleep(Duration::from_secs(backoff)).await;
-
-            // Double the back off
-            backoff <<= 2;
+            time::sleep(backoff).await;
         }
     }
 }
+
+/// Time to wait before accepting again after `failures` consecutive failures,
+/// doubling from 1 second up to 64 seconds. `None` once accepting failed too
+/// many times.
+fn accept_backoff(failures: u32) -> Option<Duration> {
+    match failures {
+        1..=7 => Some(Duration::from_secs(1 << (failures - 1))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::server::listener::accept_backoff;
+
+    /// The wait doubles after each failure, and accepting gives up after the
+    /// 64 seconds wait.
+    #[test]
+    fn accept_backoff_doubles() {
+        let backoffs: Vec<u64> = (1..)
+            .map_while(accept_backoff)
+            .map(|backoff| backoff.as_secs())
+            .collect();
+        assert_eq!(vec![1, 2, 4, 8, 16, 32, 64], backoffs);
+        assert_eq!(None, accept_backoff(8));
+        assert_eq!(Some(Duration::from_secs(1)), accept_backoff(1));
+    }
+}
@@ -3,18 +3,27 @@
 //! Provides an async `run` function that listens for inbound connections,
 //! spawning one task per connection.
 
-use std::future::Future;
+use std::future::{self, Future};
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::Poll;
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use socket2::{Domain, Protocol, Socket, Type};
 use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, Semaphore};
+use tokio::time;
 
-use crate::consts::MAX_CONNECTIONS;
+use crate::config::ServerConfig;
+use crate::consts::{DEFAULT_SHUTDOWN_TIMEOUT, MAX_CONNECTIONS};
+use crate::error::MiniRedisServerError;
 use crate::server::listener::Listener;
 use crate::storage::db::DbDropGuard;
 
 mod handler;
+pub mod info;
 pub(crate) mod listener;
 pub(crate) mod shutdown;
 
@@ -26,8 +35,88 @@ pub(crate) mod shutdown;
 /// gracefully.
 ///
 /// `tokio::signal::ctrl_c()` can be used as the `shutdown` argument. This will
-/// listen for a SIGINT signal.
+/// listen for a SIGINT signal. If `shutdown` is already complete, the server
+/// returns right away without accepting any connection.
 pub async fn run(listener: TcpListener, shutdown: impl Future) {
+    serve(listener, shutdown, ServerConfig::default()).await
+}
+
+/// Bind a listener to `addr`, to pass to [`run`].
+///
+/// Unlike `TcpListener::bind`, binding the IPv6 unspecified address `[::]`
+/// explicitly accepts both IPv6 and IPv4-mapped connections, whatever the
+/// host's default. Binding any other IPv6 address only accepts IPv6
+/// connections.
+///
+/// Must be called from within a Tokio runtime.
+pub fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+
+    if let SocketAddr::V6(v6) = addr {
+        socket.set_only_v6(!v6.ip().is_unspecified())?;
+    }
+    // Allow restarting the server while connections from a previous run are
+    // in `TIME_WAIT`, as `TcpListener::bind` does.
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+
+    TcpListener::from_std(socket.into())
+}
+
+/// A source of shutdown, see [`run_with_signals`].
+pub type ShutdownSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Run the mini-redis server until any of `signals` completes.
+///
+/// Behaves like [`run`], for servers which can be shut down from several
+/// sources, e.g. SIGINT, SIGTERM or a programmatic handle. If `signals` is
+/// empty, the server runs forever.
+pub async fn run_with_signals(listener: TcpListener, signals: Vec<ShutdownSignal>) {
+    run(listener, any_signal(signals)).await
+}
+
+/// Returns a future completing as soon as any of `signals` completes.
+///
+/// Use it to shut down a server started with [`run_with_config`] from several
+/// sources.
+pub fn any_signal(mut signals: Vec<ShutdownSignal>) -> impl Future<Output = ()> {
+    future::poll_fn(move |cx| {
+        for signal in signals.iter_mut() {
+            if signal.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(());
+            }
+        }
+        Poll::Pending
+    })
+}
+
+/// Run the mini-redis server with the given `config`.
+///
+/// Behaves like [`run`], with the server tuned by `config`.
+///
+/// # Errors
+///
+/// Returns `MiniRedisServerError::InvalidConfig`, without accepting any
+/// connection, if `config` is invalid, see [`ServerConfig::validate`].
+pub async fn run_with_config(
+    listener: TcpListener,
+    shutdown: impl Future,
+    config: ServerConfig,
+) -> Result<(), MiniRedisServerError> {
+    config.validate()?;
+    serve(listener, shutdown, config).await;
+    Ok(())
+}
+
+/// Run the server with an already validated `config`.
+async fn serve(listener: TcpListener, shutdown: impl Future, config: ServerConfig) {
+    let max_connections = config.max_connections.unwrap_or(MAX_CONNECTIONS);
+    let shutdown_timeout = config.shutdown_timeout.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+
     info!(
         "mini-redis server started listen on: {}",
         listener.local_addr().unwrap()
@@ -42,9 +131,9 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
 
     // Initialize the listener state
     let mut server = Listener {
-        listener,
-        db_holder: DbDropGuard::new(),
-        limit_connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
+        listener: Arc::new(listener),
+        db_holder: DbDropGuard::new(config),
+        limit_connections: Arc::new(Semaphore::new(max_connections)),
         notify_shutdown,
         shutdown_complete_tx,
         shutdown_complete_rx,
@@ -90,6 +179,7 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
     // explicitly drop `shutdown_transmitter`. This is important, as the
     // `.await` below would otherwise never complete.
     let Listener {
+        db_holder,
         mut shutdown_complete_rx,
         shutdown_complete_tx,
         notify_shutdown,
@@ -106,5 +196,21 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
     // handle held by the listener has been dropped above, the only remaining
     // `Sender` instances are held by connection handler tasks. When those drop,
     // the `mpsc` channel will close and `recv()` will return `None`.
-    let _ = shutdown_complete_rx.recv().await;
+    //
+    // A connection may not notice the shutdown, e.g. while blocked writing to
+    // a client which does not read, so stop waiting after the timeout.
+    if time::timeout(shutdown_timeout, shutdown_complete_rx.recv())
+        .await
+        .is_err()
+    {
+        warn!(
+            "connections still active after {:?}, shutting down anyway",
+            shutdown_timeout
+        );
+    }
+
+    info!(
+        "mini-redis server shut down after {}s uptime",
+        db_holder.db().server_info().uptime_in_seconds()
+    );
 }
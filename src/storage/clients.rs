@@ -0,0 +1,95 @@
+//! Registry of the connections currently served by the server
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use log::warn;
+
+use crate::connection::connect::ConnectionStats;
+
+/// Information tracked for a connected client.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientInfo {
+    /// Address of the remote peer.
+    pub(crate) addr: SocketAddr,
+
+    /// Traffic counters of the client's connection.
+    pub(crate) stats: Arc<ConnectionStats>,
+}
+
+/// Connected clients, keyed by connection id.
+///
+/// A connection registers itself when its handler is created and deregisters
+/// when the handler is dropped, so the registry only ever contains live
+/// connections.
+#[derive(Debug, Default)]
+pub(crate) struct Clients {
+    /// Registered clients. Guarded by its own mutex so that registering a
+    /// connection never contends with the key-value store.
+    clients: Mutex<HashMap<u64, ClientInfo>>,
+
+    /// Identifier to use for the next registered client.
+    next_id: AtomicU64,
+
+    /// Commands applied by the clients already deregistered. Only updated
+    /// under the registry lock.
+    retired_commands: AtomicU64,
+}
+
+impl Clients {
+    /// Register a client and return its unique connection id.
+    pub(crate) fn register(&self, info: ClientInfo) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.lock().insert(id, info);
+        id
+    }
+
+    /// Remove the client with the given connection id, returning its info
+    /// if it was registered.
+    pub(crate) fn deregister(&self, id: u64) -> Option<ClientInfo> {
+        let mut clients = self.lock();
+        let info = clients.remove(&id)?;
+        // Still under the registry lock, so `commands_applied` never sees the
+        // client's commands twice or not at all.
+        self.retired_commands
+            .fetch_add(info.stats.commands_applied(), Ordering::Relaxed);
+        Some(info)
+    }
+
+    /// Returns the connected clients, ordered by connection id.
+    pub(crate) fn list(&self) -> Vec<(u64, ClientInfo)> {
+        let clients = self.lock();
+        let mut list: Vec<_> = clients
+            .iter()
+            .map(|(id, info)| (*id, info.clone()))
+            .collect();
+        list.sort_by_key(|(id, _)| *id);
+        list
+    }
+
+    /// Returns the number of connected clients.
+    pub(crate) fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Total number of commands applied by every client, connected or not.
+    pub(crate) fn commands_applied(&self) -> u64 {
+        let clients = self.lock();
+        let live: u64 = clients
+            .values()
+            .map(|info| info.stats.commands_applied())
+            .sum();
+        self.retired_commands.load(Ordering::Relaxed) + live
+    }
+
+    /// Acquire the registry lock, recovering it if a panic poisoned it. The
+    /// registry is never left half-updated, so it is safe to keep using it.
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, ClientInfo>> {
+        self.clients.lock().unwrap_or_else(|poisoned| {
+            warn!("recovering clients lock poisoned by a panic");
+            self.clients.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
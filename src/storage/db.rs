@@ -1,13 +1,75 @@
-use std::sync::{Arc, Mutex};
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BinaryHeap;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use bytes::Bytes;
-use log::{debug, info};
+use bytes::{Bytes, BytesMut};
+use log::{debug, info, warn};
+use rand::Rng;
 use tokio::sync::{broadcast, Notify};
 use tokio::time::{self, Duration, Instant};
 
-use crate::storage::store::{Entry, Store};
+use crate::config::ServerConfig;
+use crate::connection::connect::ConnectionStats;
+use crate::connection::frame::{parse_signed_decimal, Frame};
+use crate::server::info::ServerInfo;
+use crate::storage::clients::{ClientInfo, Clients};
+use crate::storage::glob;
+use crate::storage::history::{History, HistoryEntry};
+use crate::storage::metrics::Metrics;
+use crate::storage::store::{Channel, Entry, Store};
 use crate::storage::traits::KvStore;
 
+/// Number of keys sized under a single acquisition of the store lock by
+/// `Db::biggest_keys`.
+const BIGKEYS_BATCH_SIZE: usize = 1024;
+
+/// Number of keys iterated between two checks of the command budget, see
+/// `ServerConfig::command_budget`.
+const BUDGET_CHECK_INTERVAL: usize = 1024;
+
+/// Time budget of a command iterating the keyspace, see
+/// `ServerConfig::command_budget`.
+struct Budget {
+    /// `Instant` past which the command is aborted, `None` if unbounded.
+    deadline: Option<Instant>,
+
+    /// Number of keys iterated so far.
+    iterated: usize,
+}
+
+impl Budget {
+    /// Count an iterated key, returning `true` once the budget is exceeded.
+    ///
+    /// The clock is only read every `BUDGET_CHECK_INTERVAL` keys.
+    fn exceeded(&mut self) -> bool {
+        self.iterated += 1;
+        self.iterated.is_multiple_of(BUDGET_CHECK_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Hash ordering the keys iterated by `SCAN`.
+///
+/// The hasher is created with fixed keys, so the hash of a key is stable for
+/// the lifetime of the process.
+fn scan_hash(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Estimated memory used by `key` and its `entry`.
+///
+/// Only counts the key and the value, not the bookkeeping.
+fn estimated_memory(key: &str, entry: &Entry) -> usize {
+    key.len() + entry.data.len()
+}
+
 /// A wrapper around a `Db` instance. This exists to allow orderly cleanup
 /// of the `Db` by signalling the background purge task to shut down when
 /// this struct is dropped.
@@ -21,8 +83,10 @@ pub(crate) struct DbDropGuard {
 impl DbDropGuard {
     /// Create a new `DbHolder`, wrapping a `Db` instance. When this is dropped
     /// the `Db`'s purge task will be shut down.
-    pub(crate) fn new() -> DbDropGuard {
-        DbDropGuard { db: Db::new() }
+    pub(crate) fn new(config: ServerConfig) -> DbDropGuard {
+        DbDropGuard {
+            db: Db::new(config),
+        }
     }
 
     /// Get the shared database. Internally, this is an `Arc`,
@@ -61,11 +125,18 @@ pub(crate) struct Db {
 impl Db {
     /// Create a new, empty, `Db` instance. Allocates shared store and spawns a
     /// background task to manage key expiration.
-    pub(crate) fn new() -> Db {
-        let shared = Arc::new(SharedDb::new());
+    pub(crate) fn new(config: ServerConfig) -> Db {
+        let shared = Arc::new(SharedDb::new(config));
 
-        // Start the background task.
-        tokio::spawn(Db::purge_expired_tasks(shared.clone()));
+        // Start the background task, unless expired keys should only be
+        // expired lazily.
+        if !shared.config.disable_purge_task {
+            tokio::spawn(Db::purge_expired_tasks(shared.clone()));
+        }
+
+        if let Some(interval) = shared.config.stats_log_interval {
+            tokio::spawn(Db::log_stats_task(shared.clone(), interval));
+        }
 
         Db { shared }
     }
@@ -99,12 +170,74 @@ impl Db {
         info!("Purge background task shut down")
     }
 
+    /// Routine executed by the stats log background task.
+    ///
+    /// Logs a summary line every `period` until `shutdown` is set.
+    async fn log_stats_task(shared: Arc<SharedDb>, period: Duration) {
+        let mut interval = time::interval(period);
+        // The first tick completes immediately, there is nothing to report yet.
+        interval.tick().await;
+
+        let mut last_commands = shared.clients.commands_applied();
+        let mut last_tick = Instant::now();
+
+        loop {
+            interval.tick().await;
+            if shared.is_shutdown() {
+                break;
+            }
+
+            let commands = shared.clients.commands_applied();
+            let now = Instant::now();
+            let per_sec = (commands - last_commands) as f64 / (now - last_tick).as_secs_f64();
+            last_commands = commands;
+            last_tick = now;
+
+            info!("{}", Db::stats_line(&shared, per_sec));
+        }
+
+        info!("Stats log background task shut down")
+    }
+
+    /// Formats the summary line logged by the stats log background task,
+    /// given the rate of commands applied since the previous line.
+    fn stats_line(shared: &SharedDb, commands_per_sec: f64) -> String {
+        let (keys, memory) = {
+            let store = shared.read_store();
+            let memory: usize = store
+                .entries
+                .iter()
+                .map(|(key, entry)| estimated_memory(key, entry))
+                .sum();
+            (store.entries.len(), memory)
+        };
+
+        let hits = shared.metrics.keyspace_hits();
+        let lookups = hits + shared.metrics.keyspace_misses();
+        let hit_ratio = if lookups == 0 {
+            0.0
+        } else {
+            hits as f64 / lookups as f64
+        };
+
+        format!(
+            "stats: clients={} keys={} commands/sec={:.2} hit_ratio={:.2} memory={}B \
+             lock_contentions={}",
+            shared.clients.len(),
+            keys,
+            commands_per_sec,
+            hit_ratio,
+            memory,
+            shared.metrics.lock_contentions()
+        )
+    }
+
     /// Signals the purge background task to shut down. This is called by the
     /// `DbShutdown`s `Drop` implementation.
     fn shutdown_purge_task(&self) {
         // The background task must be signaled to shut down. This is done by
         // setting `Store::shutdown` to `true` and signalling the task.
-        let mut store = self.shared.store.lock().unwrap();
+        let mut store = self.shared.lock_store();
         store.shutdown = true;
 
         // Drop the lock before signalling the background task. This helps
@@ -113,30 +246,201 @@ impl Db {
         drop(store);
         self.shared.background_task.notify_one();
     }
-}
 
-impl KvStore for Db {
-    /// Get the value associated with a key.
+    /// Returns the configuration the server was started with.
+    pub(crate) fn config(&self) -> &ServerConfig {
+        &self.shared.config
+    }
+
+    /// Start the time budget of a command iterating the keyspace, see
+    /// `ServerConfig::command_budget`.
+    fn budget(&self) -> Budget {
+        Budget {
+            deadline: self
+                .shared
+                .config
+                .command_budget
+                .and_then(|budget| Instant::now().checked_add(budget)),
+            iterated: 0,
+        }
+    }
+
+    /// Apply the configured TTL jitter to `ttl`, see `ServerConfig::ttl_jitter`.
     ///
-    /// Returns `None` if there is no value associated with the key. This may be
-    /// due to never having assigned a value to the key or a previously assigned
-    /// value expired.
-    fn get(&self, key: &str) -> Option<Bytes> {
-        // Acquire the lock, get the entry and clone the value.
-        //
-        // Because data is stored using `Bytes`, a clone here is a shallow
-        // clone. Data is not copied.
-        let store = self.shared.store.lock().unwrap();
-        store.entries.get(key).map(|entry| entry.data.clone())
+    /// Saturates at `Duration::MAX` rather than overflowing.
+    fn jittered(&self, ttl: Duration) -> Duration {
+        match self.shared.config.ttl_jitter {
+            Some(jitter) if jitter > 0.0 => {
+                let jitter = jitter.min(100.0) / 100.0;
+                let factor = 1.0 + rand::thread_rng().gen_range(-jitter..=jitter);
+                Duration::try_from_secs_f64(ttl.as_secs_f64() * factor).unwrap_or(Duration::MAX)
+            }
+            _ => ttl,
+        }
     }
 
-    /// Set the value associated with a key along with an optional expiration
-    /// Duration.
+    /// Encode a value before storing it, see `ServerConfig::value_codec`.
+    fn encode(&self, value: Bytes) -> Bytes {
+        match &self.shared.config.value_codec {
+            Some(codec) => codec.encode(value),
+            None => value,
+        }
+    }
+
+    /// Decode a stored value before returning it, see
+    /// `ServerConfig::value_codec`.
+    fn decode(&self, value: Bytes) -> Bytes {
+        match &self.shared.config.value_codec {
+            Some(codec) => codec.decode(value),
+            None => value,
+        }
+    }
+
+    /// Returns `true` if pub/sub is allowed on `channel`.
     ///
-    /// If a value is already associated with the key, it is removed.
-    fn set(&self, key: String, value: Bytes, expire: Option<Duration>) {
-        let mut store = self.shared.store.lock().unwrap();
+    /// Every channel is allowed unless `ServerConfig::strict_channels` is set,
+    /// in which case only the declared channels are.
+    pub(crate) fn is_channel_allowed(&self, channel: &str) -> bool {
+        let config = &self.shared.config;
+        !config.strict_channels || config.declared_channels.contains(channel)
+    }
+
+    /// Register a newly connected client. Returns the connection id used to
+    /// deregister it once the connection terminates.
+    pub(crate) fn register_client(&self, addr: SocketAddr, stats: Arc<ConnectionStats>) -> u64 {
+        self.shared.clients.register(ClientInfo { addr, stats })
+    }
+
+    /// Remove a client from the shared state once its connection terminates.
+    pub(crate) fn deregister_client(&self, id: u64) -> Option<ClientInfo> {
+        self.shared.clients.deregister(id)
+    }
+
+    /// Returns the currently connected clients, ordered by connection id.
+    pub(crate) fn clients(&self) -> Vec<(u64, ClientInfo)> {
+        self.shared.clients.list()
+    }
+
+    /// Record a command received from `addr`, if the command history is
+    /// enabled.
+    pub(crate) fn record_command(&self, addr: SocketAddr, frame: &Frame) {
+        self.shared.history.record(addr, frame);
+    }
+
+    /// Returns the last commands received by the server, oldest first.
+    pub(crate) fn history(&self) -> Vec<HistoryEntry> {
+        self.shared.history.list()
+    }
+
+    /// Purge the expired keys right away. Returns the number of keys purged.
+    pub(crate) fn purge_expired(&self) -> usize {
+        let mut store = self.shared.lock_store();
+        let before = store.entries.len();
+        store.purge_expired(Instant::now());
+        before - store.entries.len()
+    }
 
+    /// Returns the number of keys in the store, and the number of those which
+    /// have not expired.
+    pub(crate) fn key_counts(&self) -> (usize, usize) {
+        let store = self.shared.read_store();
+        let now = Instant::now();
+        let live = store
+            .entries
+            .values()
+            .filter(|entry| !entry.is_expired(now))
+            .count();
+        (store.entries.len(), live)
+    }
+
+    /// Returns the value of `key` as stored, i.e. before the value codec
+    /// decodes it, or `None` if the key is missing or expired.
+    ///
+    /// Unlike `get`, this is not counted as a keyspace lookup.
+    pub(crate) fn raw_value(&self, key: &str) -> Option<Bytes> {
+        let store = self.shared.read_store();
+        store
+            .entries
+            .get(key)
+            .filter(|entry| !entry.is_expired(Instant::now()))
+            .map(|entry| entry.data.clone())
+    }
+
+    /// Returns the `n` largest keys with their estimated memory, largest
+    /// first.
+    ///
+    /// All the keys are first cloned under a single acquisition of the read
+    /// lock. They are then sized in batches, releasing the store lock between
+    /// batches. Keys set or removed meanwhile are sized as of their batch, or
+    /// skipped.
+    pub(crate) fn biggest_keys(&self, n: usize) -> Vec<(String, usize)> {
+        let keys: Vec<String> = self.shared.read_store().entries.keys().cloned().collect();
+
+        // `n` comes from the client, so it is bounded before allocating.
+        let n = n.min(keys.len());
+
+        // Min-heap of the `n` largest keys seen so far.
+        let mut largest = BinaryHeap::with_capacity(n + 1);
+        for batch in keys.chunks(BIGKEYS_BATCH_SIZE) {
+            let store = self.shared.read_store();
+            let now = Instant::now();
+            for key in batch {
+                let entry = match store.entries.get(key) {
+                    Some(entry) if !entry.is_expired(now) => entry,
+                    _ => continue,
+                };
+                largest.push(Reverse((estimated_memory(key, entry), key)));
+                if largest.len() > n {
+                    largest.pop();
+                }
+            }
+        }
+
+        largest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((memory, key))| (key.clone(), memory))
+            .collect()
+    }
+
+    /// Returns information about the running server, such as its uptime.
+    pub(crate) fn server_info(&self) -> &ServerInfo {
+        &self.shared.info
+    }
+
+    /// Returns the node id reported by `CLUSTER MYID`.
+    pub(crate) fn node_id(&self) -> &str {
+        &self.shared.node_id
+    }
+
+    /// Returns the number of currently connected clients.
+    pub(crate) fn num_clients(&self) -> usize {
+        self.shared.clients.len()
+    }
+
+    /// Reclaim the keys which are due from the locked `store`, if the purge
+    /// task is disabled.
+    ///
+    /// Expired keys are otherwise never removed, so every write adding a key
+    /// calls this to keep the store from growing with them.
+    fn reclaim_expired(&self, store: &mut Store) {
+        if self.shared.config.disable_purge_task {
+            store.purge_expired(Instant::now());
+        }
+    }
+
+    /// Insert the already encoded `value` for `key` into the locked `store`,
+    /// replacing any previous entry and its expiration.
+    ///
+    /// Returns the previous entry, and whether the background task must be
+    /// notified because the new expiration is the next one.
+    fn insert(
+        &self,
+        store: &mut Store,
+        key: String,
+        value: Bytes,
+        expires_at: Option<Instant>,
+    ) -> (Option<Entry>, bool) {
         // Get and increment the next insertion ID. Guarded by the lock, this
         // ensures a unique identifier is associated with each `set` operation.
         let id = store.next_id;
@@ -149,10 +453,7 @@ impl KvStore for Db {
         // `set` routine.
         let mut notify = false;
 
-        let expires_at = expire.map(|duration| {
-            // `Instant` at which the key expires.
-            let when = Instant::now() + duration;
-
+        if let Some(when) = expires_at {
             // Only notify the worker task if the newly inserted expiration is the
             // **next** key to evict. In this case, the worker needs to be woken up
             // to update its state.
@@ -163,8 +464,7 @@ impl KvStore for Db {
 
             // Track the expiration.
             store.expirations.insert((when, id), key.clone());
-            when
-        });
+        }
 
         // Insert the entry into the `HashMap`.
         let prev = store.entries.insert(
@@ -179,13 +479,366 @@ impl KvStore for Db {
         // If there was a value previously associated with the key **and** it
         // had an expiration time. The associated entry in the `expirations` map
         // must also be removed. This avoids leaking data.
-        if let Some(prev) = prev {
+        if let Some(prev) = &prev {
             if let Some(when) = prev.expires_at {
                 // clear expiration
                 store.expirations.remove(&(when, prev.id));
             }
         }
 
+        (prev, notify)
+    }
+}
+
+impl KvStore for Db {
+    /// Get the value associated with a key.
+    ///
+    /// Returns `None` if there is no value associated with the key. This may be
+    /// due to never having assigned a value to the key or a previously assigned
+    /// value expired.
+    fn get(&self, key: &str) -> Option<Bytes> {
+        // Acquire the read lock, get the entry and clone the value. Concurrent
+        // reads do not wait on each other.
+        //
+        // Because data is stored using `Bytes`, a clone here is a shallow
+        // clone. Data is not copied.
+        let store = self.shared.read_store_for("get");
+
+        // The key may have expired without the background task having purged
+        // it yet (or the task may be disabled). It is never returned.
+        let (value, expired) = match store.entries.get(key) {
+            Some(entry) if entry.is_expired(Instant::now()) => (None, true),
+            Some(entry) => (Some(self.decode(entry.data.clone())), false),
+            None => (None, false),
+        };
+        drop(store);
+
+        // Expire the key lazily. The write lock is only taken in that case,
+        // and the key may have been set again before it was acquired.
+        if expired && !self.config().disable_lazy_expiry {
+            let mut store = self.shared.lock_store_for("get");
+            if store
+                .entries
+                .get(key)
+                .is_some_and(|entry| entry.is_expired(Instant::now()))
+            {
+                store.remove(key);
+            }
+        }
+
+        self.shared.metrics.record_lookup(value.is_some());
+        value
+    }
+
+    /// Get the values associated with each key, in order, under a single
+    /// acquisition of the store lock.
+    ///
+    /// Expired keys are not returned, but are left for the purge task or
+    /// `get` to remove, so that only the read lock is needed.
+    fn mget(&self, keys: &[String]) -> Vec<Option<Bytes>> {
+        let store = self.shared.read_store_for("mget");
+
+        let now = Instant::now();
+        let values: Vec<Option<Bytes>> = keys
+            .iter()
+            .map(|key| {
+                store
+                    .entries
+                    .get(key)
+                    .filter(|entry| !entry.is_expired(now))
+                    .map(|entry| entry.data.clone())
+            })
+            .collect();
+        drop(store);
+
+        values
+            .into_iter()
+            .map(|value| {
+                self.shared.metrics.record_lookup(value.is_some());
+                value.map(|value| self.decode(value))
+            })
+            .collect()
+    }
+
+    /// Returns `true` if a value is associated with the key.
+    ///
+    /// A key which expired is reported as missing, whether or not it has been
+    /// removed yet. The key is left for the purge task or `get` to remove.
+    fn exists(&self, key: &str) -> bool {
+        let store = self.shared.read_store_for("exists");
+
+        store
+            .entries
+            .get(key)
+            .is_some_and(|entry| !entry.is_expired(Instant::now()))
+    }
+
+    /// Set the value associated with a key, only if no value is associated
+    /// with it yet. Returns `true` if the value was set.
+    ///
+    /// A key which expired, whether or not it has been removed yet, counts as
+    /// missing.
+    fn set_nx(&self, key: String, value: Bytes) -> bool {
+        // Encode before acquiring the lock, the codec may be costly.
+        let value = self.encode(value);
+
+        let mut store = self.shared.lock_store_for("setnx");
+        self.reclaim_expired(&mut store);
+
+        let exists = store
+            .entries
+            .get(&key)
+            .is_some_and(|entry| !entry.is_expired(Instant::now()));
+        if exists {
+            return false;
+        }
+
+        // Replacing an expired entry drops its expiration. Without an
+        // expiration, the background task is never notified.
+        self.insert(&mut store, key, value, None);
+        true
+    }
+
+    /// Set the value associated with each key, discarding their time to live,
+    /// under a single acquisition of the store lock.
+    fn mset(&self, pairs: Vec<(String, Bytes)>) {
+        // Encode before acquiring the lock, the codec may be costly.
+        let pairs: Vec<(String, Bytes)> = pairs
+            .into_iter()
+            .map(|(key, value)| (key, self.encode(value)))
+            .collect();
+
+        let mut store = self.shared.lock_store_for("mset");
+        self.reclaim_expired(&mut store);
+
+        // Without an expiration, the background task is never notified.
+        for (key, value) in pairs {
+            self.insert(&mut store, key, value, None);
+        }
+    }
+
+    /// Set the value associated with a key, discarding its time to live, and
+    /// return the previous value.
+    fn getset(&self, key: String, value: Bytes) -> Option<Bytes> {
+        // Encode before acquiring the lock, the codec may be costly.
+        let value = self.encode(value);
+
+        let mut store = self.shared.lock_store_for("getset");
+        self.reclaim_expired(&mut store);
+
+        // Without an expiration, the background task is never notified.
+        let (prev, _) = self.insert(&mut store, key, value, None);
+        drop(store);
+
+        // The previous value may have expired without being purged yet.
+        prev.filter(|entry| !entry.is_expired(Instant::now()))
+            .map(|entry| self.decode(entry.data))
+    }
+
+    /// Returns the keys matching the glob-style `pattern`, in no particular
+    /// order.
+    ///
+    /// Expired keys are skipped, whether or not they have been removed yet.
+    ///
+    /// Returns `None` if the pass exceeded `ServerConfig::command_budget`.
+    fn keys(&self, pattern: &str) -> Option<Vec<String>> {
+        let store = self.shared.read_store_for("keys");
+
+        let now = Instant::now();
+        let mut budget = self.budget();
+        let mut keys = vec![];
+        for (key, entry) in store.entries.iter() {
+            if budget.exceeded() {
+                return None;
+            }
+            if !entry.is_expired(now) && glob::matches(pattern.as_bytes(), key.as_bytes()) {
+                keys.push(key.clone());
+            }
+        }
+
+        Some(keys)
+    }
+
+    /// Returns the next page of an incremental iteration over the keys, and
+    /// the cursor to resume from, 0 once the iteration is complete.
+    ///
+    /// A page covers about `count` keys, of which those matching the
+    /// glob-style `pattern`, if any, are returned.
+    ///
+    /// Keys are iterated in the order of their hash, and the cursor is the
+    /// hash to resume from. This needs no state between calls, and a key
+    /// present during the whole iteration is returned exactly once whatever
+    /// the keys added or removed meanwhile. The price is that each call hashes
+    /// every key under the read lock, so a call is O(N) whatever `count`, and
+    /// only the keys of the page are cloned.
+    ///
+    /// Returns `None` if the pass exceeded `ServerConfig::command_budget`.
+    fn scan(&self, cursor: u64, pattern: Option<&str>, count: usize) -> Option<(u64, Vec<String>)> {
+        let store = self.shared.read_store_for("scan");
+
+        let mut budget = self.budget();
+        let mut candidates: Vec<(u64, &String, &Entry)> = vec![];
+        for (key, entry) in store.entries.iter() {
+            if budget.exceeded() {
+                return None;
+            }
+            let hash = scan_hash(key);
+            if hash >= cursor {
+                candidates.push((hash, key, entry));
+            }
+        }
+
+        // Keep the `count` keys with the lowest hashes. Keys sharing the hash
+        // of the last one are kept too, as the next cursor skips that hash.
+        let mut next = 0;
+        if candidates.len() > count {
+            let last = candidates
+                .select_nth_unstable_by_key(count.max(1) - 1, |c| c.0)
+                .1
+                 .0;
+            candidates.retain(|(hash, _, _)| *hash <= last);
+            // No key has a hash above `u64::MAX`, the iteration is then done.
+            next = last.checked_add(1).unwrap_or(0);
+        }
+
+        let now = Instant::now();
+        let keys = candidates
+            .into_iter()
+            .filter(|(_, key, entry)| {
+                !entry.is_expired(now)
+                    && pattern
+                        .is_none_or(|pattern| glob::matches(pattern.as_bytes(), key.as_bytes()))
+            })
+            .map(|(_, key, _)| key.clone())
+            .collect();
+
+        Some((next, keys))
+    }
+
+    /// Returns the number of keys which have not expired.
+    ///
+    /// Expired keys the purge task did not remove yet are filtered out rather
+    /// than purged, so only the read lock is needed.
+    fn db_size(&self) -> usize {
+        let store = self.shared.read_store_for("dbsize");
+        let now = Instant::now();
+        store
+            .entries
+            .values()
+            .filter(|entry| !entry.is_expired(now))
+            .count()
+    }
+
+    /// Removes all the keys and their expirations.
+    ///
+    /// Pub/sub channels live in a separate key space and are kept, so current
+    /// subscribers keep receiving messages.
+    fn flush(&self) {
+        let mut store = self.shared.lock_store_for("flushdb");
+        store.entries.clear();
+        store.expirations.clear();
+        drop(store);
+
+        // The background task no longer has anything to purge, let it
+        // recompute when to wake up.
+        self.shared.background_task.notify_one();
+    }
+
+    /// Moves the value of `key`, along with its time to live, to `new_key`.
+    ///
+    /// Returns `None` if `key` does not exist. When `nx` is set, `new_key` is
+    /// left untouched if it exists, and `Some(false)` is returned. Expired
+    /// keys count as missing.
+    fn rename(&self, key: &str, new_key: &str, nx: bool) -> Option<bool> {
+        let mut store = self
+            .shared
+            .lock_store_for(if nx { "renamenx" } else { "rename" });
+
+        let now = Instant::now();
+        let live = |store: &Store, key: &str| {
+            store
+                .entries
+                .get(key)
+                .is_some_and(|entry| !entry.is_expired(now))
+        };
+
+        if !live(&store, key) {
+            return None;
+        }
+        // Renaming a key onto itself leaves it as is, though `RENAMENX` still
+        // reports the destination exists.
+        if key == new_key || (nx && live(&store, new_key)) {
+            return Some(!nx);
+        }
+
+        store.remove(new_key);
+
+        // The entry keeps its id and expiration instant, so only the key
+        // tracked by the expiration changes. The next expiration is the same
+        // and the background task needs no notification.
+        let entry = store.entries.remove(key)?;
+        if let Some(when) = entry.expires_at {
+            store
+                .expirations
+                .insert((when, entry.id), new_key.to_string());
+        }
+        store.entries.insert(new_key.to_string(), entry);
+
+        Some(true)
+    }
+
+    /// Returns the name of the type of the value associated with a key, as
+    /// reported by `TYPE`, or `none` if there is no value.
+    fn key_type(&self, key: &str) -> &'static str {
+        let store = self.shared.read_store_for("type");
+
+        // All values are strings. Other types map to their name here.
+        match store.entries.get(key) {
+            Some(entry) if !entry.is_expired(Instant::now()) => "string",
+            _ => "none",
+        }
+    }
+
+    /// Returns the length in bytes of the value associated with a key, 0 if
+    /// there is none.
+    fn strlen(&self, key: &str) -> usize {
+        let store = self.shared.read_store_for("strlen");
+
+        // The value codec may change the length, the value is measured as
+        // returned to clients.
+        store
+            .entries
+            .get(key)
+            .filter(|entry| !entry.is_expired(Instant::now()))
+            .map_or(0, |entry| self.decode(entry.data.clone()).len())
+    }
+
+    /// Set the value associated with a key along with an optional expiration
+    /// Duration.
+    ///
+    /// If a value is already associated with the key, it is removed.
+    ///
+    /// Returns `false`, leaving the store untouched, if the expiration
+    /// instant would overflow.
+    fn set(&self, key: String, value: Bytes, expire: Option<Duration>) -> bool {
+        // `Instant` at which the key expires, computed before the store is
+        // touched as in `expire`.
+        let expires_at = match expire {
+            Some(ttl) => match Instant::now().checked_add(self.jittered(ttl)) {
+                Some(when) => Some(when),
+                None => return false,
+            },
+            None => None,
+        };
+
+        // Encode before acquiring the lock, the codec may be costly.
+        let value = self.encode(value);
+
+        let mut store = self.shared.lock_store_for("set");
+        self.reclaim_expired(&mut store);
+
+        let (_, notify) = self.insert(&mut store, key, value, expires_at);
+
         // Release the mutex before notifying the background task. This helps
         // reduce contention by avoiding the background task waking up only to
         // be unable to acquire the mutex due to this function still holding it.
@@ -196,6 +849,193 @@ impl KvStore for Db {
             // its state to reflect a new expiration.
             self.shared.background_task.notify_one();
         }
+
+        true
+    }
+
+    /// Returns the remaining time to live of a key.
+    ///
+    /// Returns `None` if the key does not exist, and `Some(None)` if the key
+    /// has no time to live.
+    fn ttl(&self, key: &str) -> Option<Option<Duration>> {
+        let store = self.shared.read_store_for("ttl");
+
+        let now = Instant::now();
+        store
+            .entries
+            .get(key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.expires_at.map(|when| when - now))
+    }
+
+    /// Set the time to live of a key, replacing any previous one.
+    ///
+    /// Returns `false` if the key does not exist, or `None` if the expiration
+    /// instant would overflow, in which case the key is left unchanged.
+    fn expire(&self, key: &str, ttl: Duration) -> Option<bool> {
+        // Computed before the store is touched, so that an overflowing time to
+        // live leaves the tracked expirations consistent.
+        let now = Instant::now();
+        let when = now.checked_add(self.jittered(ttl))?;
+
+        let mut store = self.shared.lock_store_for("expire");
+
+        let (id, prev) = match store.entries.get(key) {
+            Some(entry) if !entry.is_expired(now) => (entry.id, entry.expires_at),
+            _ => return Some(false),
+        };
+
+        // The entry keeps its identifier, only its expiration is replaced.
+        if let Some(when) = prev {
+            store.expirations.remove(&(when, id));
+        }
+
+        // Only notify the background task if the new expiration is the next
+        // one, as in `set`.
+        let notify = store
+            .next_expiration()
+            .map(|expiration| expiration > when)
+            .unwrap_or(true);
+
+        store.expirations.insert((when, id), key.to_string());
+        if let Some(entry) = store.entries.get_mut(key) {
+            entry.expires_at = Some(when);
+        }
+
+        drop(store);
+
+        if notify {
+            self.shared.background_task.notify_one();
+        }
+
+        Some(true)
+    }
+
+    /// Remove the time to live of a key.
+    ///
+    /// Returns `false` if the key does not exist or has no time to live.
+    fn persist(&self, key: &str) -> bool {
+        let mut store = self.shared.lock_store_for("persist");
+
+        let now = Instant::now();
+        let (id, when) = match store.entries.get_mut(key) {
+            Some(entry) if !entry.is_expired(now) => match entry.expires_at.take() {
+                Some(when) => (entry.id, when),
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        // Stop tracking the expiration, so the purge task never removes the
+        // key. The task may wake up for nothing, which is harmless.
+        store.expirations.remove(&(when, id));
+        true
+    }
+
+    /// Append `value` to the value of a key, set to `value` if the key does
+    /// not exist. The time to live of the key is kept.
+    ///
+    /// Returns the length of the value after the append.
+    fn append(&self, key: String, value: Bytes) -> usize {
+        let mut store = self.shared.lock_store_for("append");
+        self.reclaim_expired(&mut store);
+
+        // An expired key counts as missing, and its expiration is dropped.
+        if store
+            .entries
+            .get(&key)
+            .is_some_and(|entry| entry.is_expired(Instant::now()))
+        {
+            store.remove(&key);
+        }
+
+        match store.entries.get_mut(&key) {
+            Some(entry) => {
+                // `Bytes` are immutable, the value is copied into a new buffer.
+                // The value codec runs under the lock.
+                let prev = self.decode(entry.data.clone());
+                let mut data = BytesMut::with_capacity(prev.len() + value.len());
+                data.extend_from_slice(&prev);
+                data.extend_from_slice(&value);
+                let len = data.len();
+                entry.data = self.encode(data.freeze());
+                len
+            }
+            None => {
+                let len = value.len();
+                let id = store.next_id;
+                store.next_id += 1;
+                store.entries.insert(
+                    key,
+                    Entry {
+                        id,
+                        data: self.encode(value),
+                        expires_at: None,
+                    },
+                );
+                len
+            }
+        }
+    }
+
+    /// Add `delta` to the integer value of a key, set to 0 first if the key
+    /// does not exist. The time to live of the key is kept.
+    ///
+    /// Returns the new value, or `None` if the value is not a base-10 integer
+    /// or the result overflows, in which case the key is left unchanged.
+    fn incr_by(&self, key: &str, delta: i64) -> Option<i64> {
+        // The lock is held across the read-modify-write, so that concurrent
+        // increments are not lost. The value codec runs under the lock.
+        let mut store = self.shared.lock_store_for("incr");
+        self.reclaim_expired(&mut store);
+
+        // An expired key counts as missing, and its expiration is dropped.
+        if store
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.is_expired(Instant::now()))
+        {
+            store.remove(key);
+        }
+
+        let current = match store.entries.get(key) {
+            Some(entry) => parse_signed_decimal(&self.decode(entry.data.clone()))?,
+            None => 0,
+        };
+        let value = current.checked_add(delta)?;
+        let data = self.encode(Bytes::from(value.to_string()));
+
+        match store.entries.get_mut(key) {
+            Some(entry) => entry.data = data,
+            None => {
+                let id = store.next_id;
+                store.next_id += 1;
+                store.entries.insert(
+                    key.to_string(),
+                    Entry {
+                        id,
+                        data,
+                        expires_at: None,
+                    },
+                );
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Remove the given keys, along with their expirations.
+    ///
+    /// Returns the number of keys that were removed. Keys that do not exist,
+    /// or have already expired, are not counted.
+    fn del(&self, keys: &[String]) -> usize {
+        let mut store = self.shared.lock_store_for("del");
+
+        let now = Instant::now();
+        keys.iter()
+            .filter_map(|key| store.remove(key))
+            .filter(|entry| !entry.is_expired(now))
+            .count()
     }
 
     /// Returns a `Receiver` for the requested channel.
@@ -206,13 +1046,19 @@ impl KvStore for Db {
         use std::collections::hash_map::Entry;
 
         // Acquire the mutex
-        let mut store = self.shared.store.lock().unwrap();
+        let mut store = self.shared.lock_store_for("subscribe");
+
+        store.channel_clock += 1;
+        let last_used = store.channel_clock;
 
         // If there is no entry for the requested channel, then create a new
         // broadcast channel and associate it with the key. If one already
         // exists, return an associated receiver.
-        match store.pub_sub.entry(key) {
-            Entry::Occupied(e) => e.get().subscribe(),
+        let rx = match store.pub_sub.entry(key) {
+            Entry::Occupied(mut e) => {
+                e.get_mut().last_used = last_used;
+                e.get().sender.subscribe()
+            }
             Entry::Vacant(e) => {
                 // No broadcast channel exists yet, so create one.
                 //
@@ -224,11 +1070,19 @@ impl KvStore for Db {
                 // When the channel's capacity fills up, publishing will result
                 // in old messages being dropped. This prevents slow consumers
                 // from blocking the entire system.
-                let (tx, rx) = broadcast::channel(1024);
-                e.insert(tx);
+                let (sender, rx) = broadcast::channel(1024);
+                e.insert(Channel { sender, last_used });
                 rx
             }
+        };
+
+        // Creating a channel is the only way the registry grows, so this is
+        // where it is kept bounded.
+        if let Some(max) = self.shared.config.max_idle_channels {
+            store.evict_idle_channels(max);
         }
+
+        rx
     }
 
     /// Publish a message to the channel. Returns the number of subscribers
@@ -236,7 +1090,7 @@ impl KvStore for Db {
     fn publish(&self, key: &str, value: Bytes) -> usize {
         debug!("publish: (key={}, len(value)={})", key, value.len());
 
-        let state = self.shared.store.lock().unwrap();
+        let state = self.shared.read_store_for("publish");
 
         state
             .pub_sub
@@ -244,47 +1098,156 @@ impl KvStore for Db {
             // On a successful message send on the broadcast channel, the number
             // of subscribers is returned. An error indicates there are no
             // receivers, in which case, `0` should be returned.
-            .map(|tx| tx.send(value).unwrap_or(0))
+            .map(|channel| channel.sender.send(value).unwrap_or(0))
             // If there is no entry for the channel key, then there are no
             // subscribers. In this case, return `0`.
             .unwrap_or(0)
     }
+
+    /// Publish each message to its channel under a single lock acquisition.
+    /// Returns the number of subscribers of each channel, in order.
+    fn publish_many(&self, messages: Vec<(String, Bytes)>) -> Vec<usize> {
+        debug!("publish many: (len(messages)={})", messages.len());
+
+        let state = self.shared.read_store_for("mpublish");
+
+        messages
+            .into_iter()
+            .map(|(key, value)| {
+                state
+                    .pub_sub
+                    .get(&key)
+                    .map(|channel| channel.sender.send(value).unwrap_or(0))
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
 struct SharedDb {
-    /// The shared store is guarded by a mutex. This is a `std::sync::Mutex` and
-    /// not a Tokio mutex. This is because there are no asynchronous operations
-    /// being performed while holding the mutex. Additionally, the critical
-    /// sections are very small.
+    /// The shared store is guarded by a read-write lock, so that reads such as
+    /// `GET` and `PUBLISH` run in parallel and only writes are exclusive. This
+    /// is a `std::sync::RwLock` and not a Tokio lock. This is because there
+    /// are no asynchronous operations being performed while holding the lock.
+    /// Additionally, the critical sections are very small.
     ///
-    /// A Tokio mutex is mostly intended to be used when locks need to be held
+    /// A Tokio lock is mostly intended to be used when locks need to be held
     /// across `.await` yield points. All other cases are **usually** best
-    /// served by a std mutex. If the critical section does not include any
+    /// served by a std lock. If the critical section does not include any
     /// async operations but is long (CPU intensive or performing blocking
-    /// operations), then the entire operation, including waiting for the mutex,
+    /// operations), then the entire operation, including waiting for the lock,
     /// is considered a "blocking" operation and `tokio::task::spawn_blocking`
     /// should be used.
-    store: Mutex<Store>,
+    store: RwLock<Store>,
 
     /// Notifies the background task handling entry expiration. The background
     /// task waits on this to be notified, then checks for expired values or the
     /// shutdown signal.
     background_task: Notify,
+
+    /// The configuration the server was started with.
+    config: ServerConfig,
+
+    /// Clients currently connected to the server.
+    clients: Clients,
+
+    /// Random identifier of this node, stable for the server's lifetime.
+    node_id: String,
+
+    /// When the server was started.
+    info: ServerInfo,
+
+    /// The last commands received by the server.
+    history: History,
+
+    /// Server-wide counters.
+    metrics: Metrics,
 }
 
 impl SharedDb {
-    fn new() -> Self {
+    fn new(config: ServerConfig) -> Self {
+        let history = History::new(config.command_history);
+
         SharedDb {
-            store: Mutex::new(Store::new()),
+            store: RwLock::new(Store::new()),
             background_task: Notify::new(),
+            config,
+            clients: Clients::default(),
+            // 40 hex characters, like a Redis cluster node id.
+            node_id: (0..20)
+                .map(|_| format!("{:02x}", rand::thread_rng().gen::<u8>()))
+                .collect(),
+            info: ServerInfo::new(),
+            history,
+            metrics: Metrics::default(),
         }
     }
 
+    /// Acquire the store lock for writing.
+    ///
+    /// If a command panicked while holding the write lock, the lock is
+    /// poisoned. The store is only mutated through short critical sections
+    /// which keep it consistent, so the poisoning is cleared rather than
+    /// taking down every later command.
+    fn lock_store(&self) -> RwLockWriteGuard<'_, Store> {
+        self.store.write().unwrap_or_else(|poisoned| {
+            warn!("recovering store lock poisoned by a panicked command");
+            self.store.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Acquire the store lock for reading, shared with other readers.
+    ///
+    /// Poisoning is recovered from as in `lock_store`.
+    fn read_store(&self) -> RwLockReadGuard<'_, Store> {
+        self.store.read().unwrap_or_else(|poisoned| {
+            warn!("recovering store lock poisoned by a panicked command");
+            self.store.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Acquire the store lock for writing on behalf of the `command`.
+    ///
+    /// When `ServerConfig::lock_wait_threshold` is set, the wait for the lock
+    /// is measured, and waits above the threshold are recorded as lock
+    /// contentions.
+    fn lock_store_for(&self, command: &str) -> RwLockWriteGuard<'_, Store> {
+        self.timed_lock(command, || self.lock_store())
+    }
+
+    /// Acquire the store lock for reading on behalf of the `command`, timing
+    /// the wait as in `lock_store_for`.
+    fn read_store_for(&self, command: &str) -> RwLockReadGuard<'_, Store> {
+        self.timed_lock(command, || self.read_store())
+    }
+
+    /// Acquire a guard with `lock`, recording a lock contention if the wait
+    /// exceeds `ServerConfig::lock_wait_threshold`.
+    fn timed_lock<G>(&self, command: &str, lock: impl FnOnce() -> G) -> G {
+        let threshold = match self.config.lock_wait_threshold {
+            Some(threshold) => threshold,
+            None => return lock(),
+        };
+
+        // The wait is wall clock time, whether or not the runtime's clock is
+        // paused.
+        let start = std::time::Instant::now();
+        let guard = lock();
+        let waited = start.elapsed();
+        if waited > threshold {
+            warn!("store lock contended: {} waited {:?}", command, waited);
+            self.metrics.record_lock_contention();
+        }
+        guard
+    }
+
     /// Purge all expired keys and return the `Instant` at which the **next**
     /// key will expire. The background task will sleep until this instant.
     fn purge_expired_keys(&self) -> Option<Instant> {
-        let mut store = self.store.lock().unwrap();
+        let mut store = self.lock_store();
 
         if store.shutdown {
             // The database is shutting down. All handles to the shared store
@@ -292,28 +1255,9 @@ impl SharedDb {
             return None;
         }
 
-        // This is needed to make the borrow checker happy. In short, `lock()`
-        // returns a `MutexGuard` and not a `&mut Store`. The borrow checker is
-        // not able to see "through" the mutex guard and determine that it is
-        // safe to access both `store.expirations` and `store.entries` mutably,
-        // so we get a "real" mutable reference to `Store` outside of the loop.
-        let store = &mut *store;
-
-        // Find all keys scheduled to expire **before** now.
-        let now = Instant::now();
-        while let Some((&(when, id), key)) = store.expirations.iter().next() {
-            if when > now {
-                // Done purging, `when` is the instant at which the next key
-                // expires. The worker task will wait until this instant.
-                return Some(when);
-            }
-
-            // The key expired, remove it
-            store.entries.remove(key);
-            store.expirations.remove(&(when, id));
-        }
-
-        None
+        // Find all keys scheduled to expire **before** now. The worker task
+        // will wait until the returned instant.
+        store.purge_expired(Instant::now())
     }
 
     /// Returns `true` if the database is shutting down
@@ -321,6 +1265,420 @@ impl SharedDb {
     /// The `shutdown` flag is set when all `Db` values have dropped, indicating
     /// that the shared store can no longer be accessed.
     fn is_shutdown(&self) -> bool {
-        self.store.lock().unwrap().shutdown
+        self.read_store().shutdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use tokio::time::{self, Duration, Instant};
+
+    use crate::config::{ServerConfig, ValueCodec};
+    use crate::connection::connect::ConnectionStats;
+    use crate::storage::db::Db;
+    use crate::storage::traits::KvStore;
+
+    /// Keys set with the same TTL expire at spread out instants, all within
+    /// the configured jitter.
+    #[tokio::test]
+    async fn ttl_jitter_spreads_expirations() {
+        let db = Db::new(ServerConfig {
+            ttl_jitter: Some(10.0),
+            ..Default::default()
+        });
+
+        let ttl = Duration::from_secs(100);
+        let start = Instant::now();
+        for i in 0..100 {
+            db.set(format!("key{}", i), Bytes::from("value"), Some(ttl));
+        }
+        let end = Instant::now();
+
+        let store = db.shared.store.read().unwrap();
+        let expirations: HashSet<Instant> = store
+            .entries
+            .values()
+            .map(|entry| entry.expires_at.unwrap())
+            .collect();
+
+        assert!(expirations.len() > 1);
+        for when in expirations {
+            assert!(when >= start + Duration::from_secs(90));
+            assert!(when <= end + Duration::from_secs(110));
+        }
+    }
+
+    /// Reverses values, so that stored values differ from the original ones.
+    #[derive(Debug)]
+    struct ReverseCodec;
+
+    impl ValueCodec for ReverseCodec {
+        fn encode(&self, value: Bytes) -> Bytes {
+            value.iter().rev().copied().collect::<Vec<u8>>().into()
+        }
+
+        fn decode(&self, value: Bytes) -> Bytes {
+            self.encode(value)
+        }
+    }
+
+    /// Values are encoded when stored and decoded when read back.
+    #[tokio::test]
+    async fn value_codec_round_trip() {
+        let db = Db::new(ServerConfig {
+            value_codec: Some(Arc::new(ReverseCodec)),
+            ..Default::default()
+        });
+
+        db.set("hello".into(), Bytes::from("world"), None);
+
+        let stored = db.shared.store.read().unwrap().entries["hello"]
+            .data
+            .clone();
+        assert_eq!(Bytes::from("dlrow"), stored);
+        assert_eq!(Some(Bytes::from("world")), db.get("hello"));
+    }
+
+    /// A command panicking while holding the store lock does not prevent
+    /// later commands from being served.
+    #[tokio::test]
+    async fn recover_from_poisoned_store() {
+        let db = Db::new(ServerConfig::default());
+        db.set("hello".into(), Bytes::from("world"), None);
+
+        let shared = db.shared.clone();
+        let res = std::thread::spawn(move || {
+            let _store = shared.store.write().unwrap();
+            panic!("command panicked");
+        })
+        .join();
+        assert!(res.is_err());
+        assert!(db.shared.store.is_poisoned());
+
+        assert_eq!(Some(Bytes::from("world")), db.get("hello"));
+        db.set("hello".into(), Bytes::from("again"), None);
+        assert_eq!(Some(Bytes::from("again")), db.get("hello"));
+        assert!(!db.shared.store.is_poisoned());
+    }
+
+    /// Channels left without subscribers are evicted, least recently used
+    /// first, so that the registry stays bounded.
+    #[tokio::test]
+    async fn idle_channels_are_bounded() {
+        let db = Db::new(ServerConfig {
+            max_idle_channels: Some(2),
+            ..Default::default()
+        });
+
+        let rx = db.subscribe("busy".into());
+        for i in 0..10 {
+            drop(db.subscribe(format!("transient{}", i)));
+        }
+
+        let store = db.shared.store.read().unwrap();
+        let mut channels: Vec<_> = store.pub_sub.keys().cloned().collect();
+        channels.sort();
+        assert_eq!(
+            vec!["busy", "transient7", "transient8", "transient9"],
+            channels
+        );
+        drop(rx);
+    }
+
+    /// The stats line summarizes the keyspace and the lookups.
+    #[tokio::test]
+    async fn stats_line_summarizes_server() {
+        let db = Db::new(ServerConfig::default());
+        db.set("hello".into(), Bytes::from("world"), None);
+        db.get("hello");
+        db.get("missing");
+
+        assert_eq!(
+            "stats: clients=0 keys=1 commands/sec=1.50 hit_ratio=0.50 memory=10B \
+             lock_contentions=0",
+            Db::stats_line(&db.shared, 1.5)
+        );
+    }
+
+    /// The total of commands applied keeps counting the clients which
+    /// disconnected.
+    #[tokio::test]
+    async fn commands_applied_outlive_clients() {
+        let db = Db::new(ServerConfig::default());
+        let addr = "127.0.0.1:6379".parse().unwrap();
+
+        let first = Arc::new(ConnectionStats::default());
+        let id = db.register_client(addr, first.clone());
+        first.record_command();
+        first.record_command();
+
+        let second = Arc::new(ConnectionStats::default());
+        db.register_client(addr, second.clone());
+        second.record_command();
+        assert_eq!(3, db.shared.clients.commands_applied());
+
+        db.deregister_client(id);
+        assert_eq!(3, db.shared.clients.commands_applied());
+    }
+
+    /// Commands waiting on the store lock above the threshold are counted as
+    /// lock contentions.
+    #[tokio::test]
+    async fn lock_contention_is_recorded() {
+        let db = Db::new(ServerConfig {
+            lock_wait_threshold: Some(Duration::from_millis(5)),
+            ..Default::default()
+        });
+        db.get("hello");
+        assert_eq!(0, db.shared.metrics.lock_contentions());
+
+        // Another thread holds the lock while the command runs.
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                let _store = db.shared.store.write().unwrap();
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            })
+        };
+        locked_rx.recv().unwrap();
+        db.get("hello");
+        holder.join().unwrap();
+
+        assert_eq!(1, db.shared.metrics.lock_contentions());
+    }
+
+    /// Readers share the store lock: a `GET` does not wait on another reader
+    /// holding it.
+    #[tokio::test]
+    async fn reads_do_not_contend() {
+        let db = Db::new(ServerConfig {
+            lock_wait_threshold: Some(Duration::from_millis(5)),
+            ..Default::default()
+        });
+        db.set("hello".into(), Bytes::from("world"), None);
+
+        // Another thread holds the read lock while the command runs.
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                let _store = db.shared.store.read().unwrap();
+                locked_tx.send(()).unwrap();
+                // Released once the command completed, or after a timeout.
+                let _ = done_rx.recv_timeout(std::time::Duration::from_secs(5));
+            })
+        };
+        locked_rx.recv().unwrap();
+        assert_eq!(Some(Bytes::from("world")), db.get("hello"));
+        done_tx.send(()).unwrap();
+        holder.join().unwrap();
+
+        assert_eq!(0, db.shared.metrics.lock_contentions());
+    }
+
+    /// Deleting a key with a TTL also drops its expiration, so the purge task
+    /// does not track it anymore.
+    #[tokio::test]
+    async fn del_removes_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(1, db.shared.store.read().unwrap().expirations.len());
+
+        assert_eq!(1, db.del(&["hello".into(), "missing".into()]));
+
+        let store = db.shared.store.read().unwrap();
+        assert!(store.entries.is_empty());
+        assert!(store.expirations.is_empty());
+    }
+
+    /// Appending to a key keeps its time to live.
+    #[tokio::test]
+    async fn append_keeps_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_secs(60)),
+        );
+        let expires_at = db.shared.store.read().unwrap().entries["hello"].expires_at;
+
+        assert_eq!(6, db.append("hello".into(), Bytes::from("!")));
+
+        let store = db.shared.store.read().unwrap();
+        assert_eq!(expires_at, store.entries["hello"].expires_at);
+        assert_eq!(1, store.expirations.len());
+        assert_eq!(b"world!", &store.entries["hello"].data[..]);
+    }
+
+    /// Swapping a value discards the key's time to live.
+    #[tokio::test]
+    async fn getset_clears_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_secs(60)),
+        );
+
+        let prev = db.getset("hello".into(), Bytes::from("again"));
+        assert_eq!(Some(Bytes::from("world")), prev);
+
+        let store = db.shared.store.read().unwrap();
+        assert_eq!(None, store.entries["hello"].expires_at);
+        assert!(store.expirations.is_empty());
+    }
+
+    /// An expired key which has not been purged yet counts as missing.
+    #[tokio::test(start_paused = true)]
+    async fn set_nx_replaces_expired_keys() {
+        let db = Db::new(ServerConfig {
+            disable_purge_task: true,
+            ..Default::default()
+        });
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_millis(10)),
+        );
+        assert!(!db.set_nx("hello".into(), Bytes::from("again")));
+
+        time::advance(Duration::from_millis(20)).await;
+        assert!(db.set_nx("hello".into(), Bytes::from("again")));
+
+        let store = db.shared.store.read().unwrap();
+        assert_eq!(None, store.entries["hello"].expires_at);
+        assert!(store.expirations.is_empty());
+    }
+
+    /// Without the purge task, every write adding a key reclaims the keys
+    /// which are due.
+    #[tokio::test(start_paused = true)]
+    async fn writes_reclaim_expired_keys_without_purge_task() {
+        let db = Db::new(ServerConfig {
+            disable_purge_task: true,
+            ..Default::default()
+        });
+        let writes: [fn(&Db); 3] = [
+            |db| assert!(db.set_nx("other".into(), Bytes::from("1"))),
+            |db| assert_eq!(1, db.append("other".into(), Bytes::from("1"))),
+            |db| assert_eq!(Some(1), db.incr_by("other", 1)),
+        ];
+        for write in writes {
+            db.set(
+                "hello".into(),
+                Bytes::from("world"),
+                Some(Duration::from_millis(10)),
+            );
+            time::advance(Duration::from_millis(20)).await;
+            assert_eq!(1, db.shared.store.read().unwrap().expirations.len());
+
+            write(&db);
+            let store = db.shared.store.read().unwrap();
+            assert!(!store.entries.contains_key("hello"));
+            assert!(store.expirations.is_empty());
+            drop(store);
+            db.del(&["other".to_string()]);
+        }
+    }
+
+    /// Setting a time to live replaces the previous expiration, and the purge
+    /// task removes the key when it is due.
+    #[tokio::test(start_paused = true)]
+    async fn expire_replaces_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(Some(true), db.expire("hello", Duration::from_millis(10)));
+        assert_eq!(1, db.shared.store.read().unwrap().expirations.len());
+
+        time::sleep(Duration::from_millis(20)).await;
+        let store = db.shared.store.read().unwrap();
+        assert!(store.entries.is_empty());
+        assert!(store.expirations.is_empty());
+    }
+
+    /// An overflowing time to live is rejected, and the previous expiration
+    /// is still tracked.
+    #[tokio::test(start_paused = true)]
+    async fn expire_rejects_overflowing_ttl() {
+        let db = Db::new(ServerConfig {
+            ttl_jitter: Some(10.0),
+            ..Default::default()
+        });
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_millis(10)),
+        );
+        assert_eq!(None, db.expire("hello", Duration::MAX));
+        assert_eq!(1, db.shared.store.read().unwrap().expirations.len());
+
+        time::sleep(Duration::from_millis(20)).await;
+        let store = db.shared.store.read().unwrap();
+        assert!(store.entries.is_empty());
+        assert!(store.expirations.is_empty());
+    }
+
+    /// Persisting a key drops its tracked expiration.
+    #[tokio::test(start_paused = true)]
+    async fn persist_removes_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_millis(10)),
+        );
+        assert!(db.persist("hello"));
+        assert!(!db.persist("hello"));
+
+        time::sleep(Duration::from_millis(20)).await;
+        let store = db.shared.store.read().unwrap();
+        assert_eq!(None, store.entries["hello"].expires_at);
+        assert!(store.expirations.is_empty());
+    }
+
+    /// The expiration follows the renamed key, and the one of the overwritten
+    /// key is dropped.
+    #[tokio::test(start_paused = true)]
+    async fn rename_moves_expiration() {
+        let db = Db::new(ServerConfig::default());
+        db.set(
+            "hello".into(),
+            Bytes::from("world"),
+            Some(Duration::from_millis(10)),
+        );
+        db.set(
+            "other".into(),
+            Bytes::from("value"),
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(Some(true), db.rename("hello", "other", false));
+
+        {
+            let store = db.shared.store.read().unwrap();
+            assert_eq!(1, store.expirations.len());
+            assert_eq!(
+                Some(&"other".to_string()),
+                store.expirations.values().next()
+            );
+        }
+
+        time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(None, db.get("other"));
+        assert!(db.shared.store.read().unwrap().entries.is_empty());
     }
 }
@@ -0,0 +1,143 @@
+//! Glob-style pattern matching, as used by `KEYS`.
+//!
+//! Patterns follow the Redis syntax:
+//!
+//! * `?` matches any single byte.
+//! * `*` matches any sequence of bytes, including the empty one.
+//! * `[abc]` matches one of the listed bytes, `[^abc]` any other byte, and
+//!   `[a-z]` a range of bytes.
+//! * `\` escapes the next byte, e.g. `\[` matches a literal `[`.
+//!
+//! Matching is done on bytes, keys do not need to be valid UTF-8.
+
+/// Returns `true` if `string` matches the glob `pattern`.
+pub(crate) fn matches(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+
+    // Positions in the pattern and the string right after the last `*`. On a
+    // mismatch, the `*` is made to match one more byte and matching resumes
+    // from there.
+    let mut star: Option<(usize, usize)> = None;
+
+    while s < string.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            star = Some((p, s));
+            continue;
+        }
+
+        if let Some(len) = pattern.get(p..).and_then(|rest| match_one(rest, string[s])) {
+            p += len;
+            s += 1;
+            continue;
+        }
+
+        match star {
+            Some((star_p, star_s)) => {
+                p = star_p;
+                s = star_s + 1;
+                star = Some((star_p, s));
+            }
+            None => return false,
+        }
+    }
+
+    // The string is consumed, only stars may remain in the pattern.
+    pattern[p..].iter().all(|&b| b == b'*')
+}
+
+/// Matches the element at the start of `pattern`, which is not a `*`, against
+/// the byte `b`. Returns the length of the element in the pattern if it
+/// matches.
+fn match_one(pattern: &[u8], b: u8) -> Option<usize> {
+    match *pattern.first()? {
+        b'?' => Some(1),
+        b'\\' if pattern.len() > 1 => (pattern[1] == b).then_some(2),
+        b'[' => match_class(pattern, b),
+        literal => (literal == b).then_some(1),
+    }
+}
+
+/// Matches the `[...]` class at the start of `pattern` against the byte `b`.
+/// Returns the length of the class in the pattern if it matches.
+///
+/// As with Redis, an unterminated class extends to the end of the pattern.
+fn match_class(pattern: &[u8], b: u8) -> Option<usize> {
+    let mut i = 1;
+    let negate = pattern.get(i) == Some(&b'^');
+    if negate {
+        i += 1;
+    }
+
+    let mut matched = false;
+    while let Some(&c) = pattern.get(i) {
+        if c == b']' {
+            i += 1;
+            break;
+        }
+
+        if c == b'\\' && i + 1 < pattern.len() {
+            matched |= pattern[i + 1] == b;
+            i += 2;
+        } else if pattern.get(i + 1) == Some(&b'-') && i + 2 < pattern.len() {
+            let end = pattern[i + 2];
+            let (low, high) = if c <= end { (c, end) } else { (end, c) };
+            matched |= low <= b && b <= high;
+            i += 3;
+        } else {
+            matched |= c == b;
+            i += 1;
+        }
+    }
+
+    (matched != negate).then_some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn wildcards() {
+        assert!(matches(b"*", b""));
+        assert!(matches(b"*", b"hello"));
+        assert!(matches(b"h*o", b"hello"));
+        assert!(matches(b"h*", b"h"));
+        assert!(matches(b"*llo", b"hello"));
+        assert!(matches(b"*l*l*", b"hello"));
+        assert!(matches(b"h?llo", b"hello"));
+        assert!(!matches(b"h?llo", b"hllo"));
+        assert!(!matches(b"h*x", b"hello"));
+        assert!(!matches(b"", b"hello"));
+        assert!(matches(b"", b""));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches(b"h[ae]llo", b"hello"));
+        assert!(matches(b"h[ae]llo", b"hallo"));
+        assert!(!matches(b"h[ae]llo", b"hillo"));
+        assert!(matches(b"h[^e]llo", b"hallo"));
+        assert!(!matches(b"h[^e]llo", b"hello"));
+        assert!(matches(b"h[a-f]llo", b"hello"));
+        assert!(matches(b"h[f-a]llo", b"hello"));
+        assert!(!matches(b"h[a-d]llo", b"hello"));
+    }
+
+    #[test]
+    fn escapes() {
+        assert!(matches(b"\\[hello\\]", b"[hello]"));
+        assert!(!matches(b"\\[hello\\]", b"h"));
+        assert!(matches(b"\\*", b"*"));
+        assert!(!matches(b"\\*", b"hello"));
+        assert!(matches(b"h\\?llo", b"h?llo"));
+        assert!(!matches(b"h\\?llo", b"hello"));
+        assert!(matches(b"[\\]]", b"]"));
+    }
+
+    #[test]
+    fn binary_keys() {
+        assert!(matches(b"\xff*", b"\xff\x00"));
+        assert!(!matches(b"\xff?", b"\xff"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Ring buffer of the last commands received by the server
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+
+use crate::connection::frame::Frame;
+
+/// Longest argument recorded, longer arguments are truncated.
+const MAX_ARG_LEN: usize = 32;
+
+/// A command recorded in the history.
+#[derive(Debug, Clone)]
+pub(crate) struct HistoryEntry {
+    /// When the command was received, in milliseconds since the Unix epoch.
+    pub(crate) unix_ms: u128,
+
+    /// Address of the client which sent the command.
+    pub(crate) addr: SocketAddr,
+
+    /// The command name and its arguments, separated by spaces.
+    pub(crate) command: String,
+}
+
+/// The last commands received by the server, see
+/// `ServerConfig::command_history`.
+#[derive(Debug)]
+pub(crate) struct History {
+    /// Recorded commands, oldest first.
+    entries: Mutex<VecDeque<HistoryEntry>>,
+
+    /// Maximum number of recorded commands. `0` disables recording.
+    capacity: usize,
+}
+
+impl History {
+    pub(crate) fn new(capacity: usize) -> History {
+        History {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Record the command received as `frame` from `addr`, evicting the
+    /// oldest recorded command if the history is full.
+    pub(crate) fn record(&self, addr: SocketAddr, frame: &Frame) {
+        // Recording is disabled, don't even format the command.
+        if self.capacity == 0 {
+            return;
+        }
+
+        let entry = HistoryEntry {
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_millis())
+                .unwrap_or(0),
+            addr,
+            command: describe(frame),
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| {
+            warn!("recovering history lock poisoned by a panic");
+            self.entries.clear_poison();
+            poisoned.into_inner()
+        });
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Returns the recorded commands, oldest first.
+    pub(crate) fn list(&self) -> Vec<HistoryEntry> {
+        match self.entries.lock() {
+            Ok(entries) => entries.iter().cloned().collect(),
+            Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
+        }
+    }
+}
+
+/// Describe a command frame as its space-separated, truncated arguments.
+fn describe(frame: &Frame) -> String {
+    let parts = match frame {
+        Frame::Array(parts) => parts,
+        frame => return format!("{:?}", frame),
+    };
+
+    let mut command = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            command.push(' ');
+        }
+
+        let arg = match part {
+            Frame::Simple(s) => String::from_utf8_lossy(s.as_bytes()),
+            Frame::Bulk(data) => String::from_utf8_lossy(data),
+            part => format!("{:?}", part).into(),
+        };
+        match arg.char_indices().nth(MAX_ARG_LEN) {
+            Some((end, _)) => {
+                command.push_str(&arg[..end]);
+                command.push_str("...");
+            }
+            None => command.push_str(&arg),
+        }
+    }
+
+    command
+}
@@ -0,0 +1,51 @@
+//! Server-wide counters
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters shared by every connection.
+///
+/// Counters are only ever incremented. Consumers such as the stats log
+/// compute rates from the difference between two readings.
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    /// Lookups of a key which was present.
+    keyspace_hits: AtomicU64,
+
+    /// Lookups of a key which was missing or expired.
+    keyspace_misses: AtomicU64,
+
+    /// Store lock acquisitions which waited longer than
+    /// `ServerConfig::lock_wait_threshold`.
+    lock_contentions: AtomicU64,
+}
+
+impl Metrics {
+    /// Record the lookup of a key, `hit` being `true` if it was present.
+    pub(crate) fn record_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.keyspace_hits
+        } else {
+            &self.keyspace_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a store lock acquisition which waited above the threshold.
+    pub(crate) fn record_lock_contention(&self) {
+        self.lock_contentions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Total number of lookups of a present key.
+    pub(crate) fn keyspace_hits(&self) -> u64 {
+        self.keyspace_hits.load(Ordering::Relaxed)
+    }
+
+    /// Total number of lookups of a missing key.
+    pub(crate) fn keyspace_misses(&self) -> u64 {
+        self.keyspace_misses.load(Ordering::Relaxed)
+    }
+
+    /// Total number of store lock contentions.
+    pub(crate) fn lock_contentions(&self) -> u64 {
+        self.lock_contentions.load(Ordering::Relaxed)
+    }
+}
@@ -1,3 +1,7 @@
+pub(crate) mod clients;
 pub mod db;
+pub(crate) mod glob;
+pub(crate) mod history;
+pub(crate) mod metrics;
 pub mod store;
 pub(crate) mod traits;
@@ -14,7 +14,10 @@ pub(crate) struct Store {
 
     /// The pub/sub key-space. Redis uses a **separate** key space for key-value
     /// and pub/sub. `mini-redis` handles this by using a separate `HashMap`.
-    pub(crate) pub_sub: HashMap<String, broadcast::Sender<Bytes>>,
+    pub(crate) pub_sub: HashMap<String, Channel>,
+
+    /// Incremented on every subscription, to order channels by last use.
+    pub(crate) channel_clock: u64,
 
     /// Tracks key TTLs.
     ///
@@ -38,6 +41,17 @@ pub(crate) struct Store {
     pub(crate) shutdown: bool,
 }
 
+/// A pub/sub channel
+#[derive(Debug)]
+pub(crate) struct Channel {
+    /// Broadcasts the messages published on the channel to its subscribers.
+    pub(crate) sender: broadcast::Sender<Bytes>,
+
+    /// Value of `Store::channel_clock` when the channel was last subscribed
+    /// to.
+    pub(crate) last_used: u64,
+}
+
 /// Entry in the key-value store
 #[derive(Debug)]
 pub(crate) struct Entry {
@@ -57,6 +71,7 @@ impl Store {
         Store {
             entries: HashMap::new(),
             pub_sub: HashMap::new(),
+            channel_clock: 0,
             expirations: BTreeMap::new(),
             next_id: 0,
             shutdown: false,
@@ -67,4 +82,65 @@ impl Store {
     pub(crate) fn next_expiration(&self) -> Option<Instant> {
         self.expirations.keys().next().map(|expire| expire.0)
     }
+
+    /// Remove the entry for `key` along with its tracked expiration, if any.
+    pub(crate) fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        if let Some(when) = entry.expires_at {
+            self.expirations.remove(&(when, entry.id));
+        }
+        Some(entry)
+    }
+
+    /// Remove all keys expired at `now` and return the `Instant` at which
+    /// the **next** key will expire.
+    pub(crate) fn purge_expired(&mut self, now: Instant) -> Option<Instant> {
+        while let Some((&(when, id), key)) = self.expirations.iter().next() {
+            if when > now {
+                // Done purging, `when` is the instant at which the next key
+                // expires.
+                return Some(when);
+            }
+
+            // The key expired, remove it
+            self.entries.remove(key);
+            self.expirations.remove(&(when, id));
+        }
+
+        None
+    }
+
+    /// Remove the least recently used channels without subscribers, so that
+    /// at most `max` of them are kept.
+    ///
+    /// Channels are not removed when their last subscriber goes away, so
+    /// transient channels would otherwise accumulate forever.
+    pub(crate) fn evict_idle_channels(&mut self, max: usize) {
+        let mut idle: Vec<(u64, String)> = self
+            .pub_sub
+            .iter()
+            .filter(|(_, channel)| channel.sender.receiver_count() == 0)
+            .map(|(name, channel)| (channel.last_used, name.clone()))
+            .collect();
+
+        if idle.len() <= max {
+            return;
+        }
+
+        let excess = idle.len() - max;
+        idle.sort_unstable();
+        for (_, name) in idle.into_iter().take(excess) {
+            self.pub_sub.remove(&name);
+        }
+    }
+}
+
+impl Entry {
+    /// Returns `true` if the entry has expired at `now`.
+    ///
+    /// An expired entry may still be present until it is purged, it must be
+    /// treated as missing.
+    pub(crate) fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.map(|when| when <= now).unwrap_or(false)
+    }
 }
@@ -5,11 +5,108 @@ use tokio::time::Duration;
 pub trait KvStore {
     fn get(&self, key: &str) -> Option<Bytes>;
 
+    /// Get the values associated with each key, in order, under a single
+    /// acquisition of the store lock.
+    fn mget(&self, keys: &[String]) -> Vec<Option<Bytes>>;
+
+    /// Returns `true` if a value is associated with the key.
+    ///
+    /// A key which expired is reported as missing, whether or not it has been
+    /// removed yet.
+    fn exists(&self, key: &str) -> bool;
+
+    /// Set the value associated with a key, only if no value is associated
+    /// with it yet. Returns `true` if the value was set.
+    fn set_nx(&self, key: String, value: Bytes) -> bool;
+
+    /// Set the value associated with each key, discarding their time to live,
+    /// under a single acquisition of the store lock.
+    fn mset(&self, pairs: Vec<(String, Bytes)>);
+
+    /// Set the value associated with a key, discarding its time to live, and
+    /// return the previous value.
+    fn getset(&self, key: String, value: Bytes) -> Option<Bytes>;
+
+    /// Returns the keys matching the glob-style `pattern`, in no particular
+    /// order.
+    ///
+    /// Returns `None` if the pass exceeded `ServerConfig::command_budget`.
+    fn keys(&self, pattern: &str) -> Option<Vec<String>>;
+
+    /// Returns the next page of an incremental iteration over the keys, and
+    /// the cursor to resume from, 0 once the iteration is complete.
+    ///
+    /// A page covers about `count` keys, of which those matching the
+    /// glob-style `pattern`, if any, are returned. Each call walks the whole
+    /// keyspace.
+    ///
+    /// Returns `None` if the pass exceeded `ServerConfig::command_budget`.
+    fn scan(&self, cursor: u64, pattern: Option<&str>, count: usize) -> Option<(u64, Vec<String>)>;
+
+    /// Returns the number of keys which have not expired.
+    fn db_size(&self) -> usize;
+
+    /// Removes all the keys and their expirations. Pub/sub channels are kept.
+    fn flush(&self);
+
+    /// Moves the value of `key`, along with its time to live, to `new_key`.
+    ///
+    /// Returns `None` if `key` does not exist. When `nx` is set, `new_key` is
+    /// left untouched if it exists, and `Some(false)` is returned.
+    fn rename(&self, key: &str, new_key: &str, nx: bool) -> Option<bool>;
+
+    /// Returns the name of the type of the value associated with a key, as
+    /// reported by `TYPE`, or `none` if there is no value.
+    fn key_type(&self, key: &str) -> &'static str;
+
+    /// Returns the length in bytes of the value associated with a key, 0 if
+    /// there is none.
+    fn strlen(&self, key: &str) -> usize;
+
     /// Set the value associated with a key along with an optional expiration
     /// Duration.
     ///
     /// If a value is already associated with the key, it is removed.
-    fn set(&self, key: String, value: Bytes, expire: Option<Duration>);
+    ///
+    /// Returns `false`, leaving the store untouched, if the expiration
+    /// instant would overflow.
+    fn set(&self, key: String, value: Bytes, expire: Option<Duration>) -> bool;
+
+    /// Returns the remaining time to live of a key.
+    ///
+    /// Returns `None` if the key does not exist, and `Some(None)` if the key
+    /// has no time to live.
+    fn ttl(&self, key: &str) -> Option<Option<Duration>>;
+
+    /// Set the time to live of a key, replacing any previous one.
+    ///
+    /// Returns `false` if the key does not exist, or `None` if the expiration
+    /// instant would overflow.
+    fn expire(&self, key: &str, ttl: Duration) -> Option<bool>;
+
+    /// Remove the time to live of a key.
+    ///
+    /// Returns `false` if the key does not exist or has no time to live.
+    fn persist(&self, key: &str) -> bool;
+
+    /// Append `value` to the value of a key, set to `value` if the key does
+    /// not exist. The time to live of the key is kept.
+    ///
+    /// Returns the length of the value after the append.
+    fn append(&self, key: String, value: Bytes) -> usize;
+
+    /// Add `delta` to the integer value of a key, set to 0 first if the key
+    /// does not exist. The time to live of the key is kept.
+    ///
+    /// Returns the new value, or `None` if the value is not a base-10 integer
+    /// or the result overflows, in which case the key is left unchanged.
+    fn incr_by(&self, key: &str, delta: i64) -> Option<i64>;
+
+    /// Remove the given keys, along with their expirations.
+    ///
+    /// Returns the number of keys that were removed. Keys that do not exist,
+    /// or have already expired, are not counted.
+    fn del(&self, keys: &[String]) -> usize;
 
     /// Returns a `Receiver` for the requested channel.
     ///
@@ -20,4 +117,8 @@ pub trait KvStore {
     /// Publish a message to the channel. Returns the number of subscribers
     /// listening on the channel.
     fn publish(&self, key: &str, value: Bytes) -> usize;
+
+    /// Publish each message to its channel under a single lock acquisition.
+    /// Returns the number of subscribers of each channel, in order.
+    fn publish_many(&self, messages: Vec<(String, Bytes)>) -> Vec<usize>;
 }
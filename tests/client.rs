@@ -1,7 +1,26 @@
+use bytes::Bytes;
+use mini_redis::error::MiniRedisConnectionError;
 use mini_redis::{client, server};
 use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// The message is echoed as is, even when empty or binary.
+#[tokio::test]
+async fn echo_returns_message() {
+    let (addr, _) = start_server().await;
+    let mut client = client::connect(addr).await.unwrap();
+
+    let echo = client.echo("hello world".into()).await.unwrap();
+    assert_eq!(b"hello world", &echo[..]);
+
+    assert!(client.echo(Bytes::new()).await.unwrap().is_empty());
+
+    let binary = Bytes::from_static(b"\x00\xff\r\n");
+    assert_eq!(binary, client.echo(binary.clone()).await.unwrap());
+}
 
 /// A PING PONG test without message provided.
 /// It should return "PONG".
@@ -39,6 +58,471 @@ async fn key_value_get_set() {
     assert_eq!(b"world", &value[..])
 }
 
+/// A time to live can be set on an existing key.
+#[tokio::test]
+async fn expire_existing_key() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert!(!client
+        .expire("hello", Duration::from_millis(50))
+        .await
+        .unwrap());
+
+    client.set("hello", "world".into()).await.unwrap();
+    assert!(client
+        .expire("hello", Duration::from_millis(50))
+        .await
+        .unwrap());
+    assert!(client.get("hello").await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(None, client.get("hello").await.unwrap());
+}
+
+/// The time to live follows the Redis conventions for missing keys and keys
+/// without expiration.
+#[tokio::test]
+async fn ttl_and_pttl() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(-2, client.ttl("hello").await.unwrap());
+    assert_eq!(-2, client.pttl("hello").await.unwrap());
+
+    client.set("hello", "world".into()).await.unwrap();
+    assert_eq!(-1, client.ttl("hello").await.unwrap());
+    assert_eq!(-1, client.pttl("hello").await.unwrap());
+
+    client
+        .set_expires("hello", "world".into(), Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(10, client.ttl("hello").await.unwrap());
+    let pttl = client.pttl("hello").await.unwrap();
+    assert!(9_000 < pttl && pttl <= 10_000, "pttl = {}", pttl);
+
+    // The time to live is discarded by GETSET.
+    client.getset("hello", "again".into()).await.unwrap();
+    assert_eq!(-1, client.pttl("hello").await.unwrap());
+}
+
+/// A persisted key is never evicted by the purge task.
+#[tokio::test]
+async fn persist_removes_time_to_live() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    assert!(!client.persist("hello").await.unwrap());
+    assert!(!client.persist("missing").await.unwrap());
+
+    client
+        .set_expires("hello", "world".into(), Duration::from_millis(50))
+        .await
+        .unwrap();
+    assert!(client.persist("hello").await.unwrap());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(b"world", &client.get("hello").await.unwrap().unwrap()[..]);
+}
+
+/// A key is only set if it does not exist, or has expired.
+#[tokio::test]
+async fn set_nx_only_sets_missing_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert!(client.set_nx("hello", "world".into()).await.unwrap());
+    assert!(!client.set_nx("hello", "again".into()).await.unwrap());
+    assert_eq!(b"world", &client.get("hello").await.unwrap().unwrap()[..]);
+
+    client
+        .set_expires("foo", "bar".into(), Duration::from_millis(50))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(client.set_nx("foo", "baz".into()).await.unwrap());
+    assert_eq!(b"baz", &client.get("foo").await.unwrap().unwrap()[..]);
+}
+
+/// All pairs are set, the last value winning for a repeated key.
+#[tokio::test]
+async fn mset_sets_all_pairs() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client
+        .set_expires("hello", "old".into(), Duration::from_millis(100))
+        .await
+        .unwrap();
+    client
+        .mset(&[
+            ("hello", "world".into()),
+            ("foo", "bar".into()),
+            ("foo", "baz".into()),
+        ])
+        .await
+        .unwrap();
+
+    // The time to live of `hello` was discarded.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let values = client.mget(&["hello", "foo"]).await.unwrap();
+    assert_eq!(
+        vec![Some(Bytes::from("world")), Some(Bytes::from("baz"))],
+        values
+    );
+}
+
+/// Values are returned in the order of the keys, with `None` for missing keys.
+#[tokio::test]
+async fn mget_preserves_order() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    client.set("foo", "bar".into()).await.unwrap();
+
+    let values = client
+        .mget(&["foo", "missing", "hello", "foo"])
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            Some(Bytes::from("bar")),
+            None,
+            Some(Bytes::from("world")),
+            Some(Bytes::from("bar")),
+        ],
+        values
+    );
+}
+
+/// The previous value is returned, and the key's time to live is discarded.
+#[tokio::test]
+async fn getset_returns_previous_value() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(None, client.getset("hello", "world".into()).await.unwrap());
+
+    client
+        .set_expires("hello", "world".into(), Duration::from_millis(100))
+        .await
+        .unwrap();
+    let prev = client.getset("hello", "again".into()).await.unwrap();
+    assert_eq!(Some(Bytes::from("world")), prev);
+
+    // The key outlives the discarded time to live.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let value = client.get("hello").await.unwrap().unwrap();
+    assert_eq!(b"again", &value[..]);
+}
+
+/// Keys are listed if they match the pattern.
+#[tokio::test]
+async fn keys_matching_pattern() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    for key in ["hello", "hallo", "hxllo", "[hello]", "foo"] {
+        client.set(key, "value".into()).await.unwrap();
+    }
+
+    let mut keys = client.keys("h[ae]llo").await.unwrap();
+    keys.sort();
+    assert_eq!(vec!["hallo", "hello"], keys);
+
+    assert_eq!(vec!["[hello]"], client.keys("\\[*").await.unwrap());
+    assert_eq!(5, client.keys("*").await.unwrap().len());
+    assert!(client.keys("bar*").await.unwrap().is_empty());
+}
+
+/// A full iteration returns each key once, and only the matching ones.
+#[tokio::test]
+async fn scan_iterates_all_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    for i in 0..100 {
+        client
+            .set(&format!("key:{}", i), "value".into())
+            .await
+            .unwrap();
+    }
+    client.set("other", "value".into()).await.unwrap();
+
+    let mut keys = vec![];
+    let mut cursor = 0;
+    let mut calls = 0;
+    loop {
+        let (next, page) = client.scan(cursor, Some("key:*"), Some(7)).await.unwrap();
+        keys.extend(page);
+        calls += 1;
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+    keys.sort();
+
+    let mut expected: Vec<String> = (0..100).map(|i| format!("key:{}", i)).collect();
+    expected.sort();
+    assert_eq!(expected, keys);
+    assert!(calls >= 101 / 7);
+
+    let (next, keys) = client.scan(0, None, Some(1000)).await.unwrap();
+    assert_eq!(0, next);
+    assert_eq!(101, keys.len());
+}
+
+/// Expired keys are not counted, even before they are purged.
+#[tokio::test]
+async fn db_size_counts_live_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(0, client.db_size().await.unwrap());
+
+    client.set("hello", "world".into()).await.unwrap();
+    client
+        .set_expires("short", "lived".into(), Duration::from_millis(50))
+        .await
+        .unwrap();
+    assert_eq!(2, client.db_size().await.unwrap());
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(1, client.db_size().await.unwrap());
+}
+
+/// Flushing removes all the keys, but keeps the subscriptions.
+#[tokio::test]
+async fn flush_db_keeps_subscriptions() {
+    let (addr, _) = start_server().await;
+
+    let subscribe_client = client::connect(addr).await.unwrap();
+    let mut subscriber = subscribe_client
+        .subscribe(vec!["hello".into()])
+        .await
+        .unwrap();
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    client
+        .set_expires("short", "lived".into(), Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    client.flush_db().await.unwrap();
+    assert_eq!(0, client.db_size().await.unwrap());
+    assert!(client.get("hello").await.unwrap().is_none());
+
+    assert_eq!(1, client.publish("hello", "world".into()).await.unwrap());
+    let message = subscriber.next_message().await.unwrap().unwrap();
+    assert_eq!("hello", &message.channel);
+    assert_eq!(b"world", &message.content[..]);
+}
+
+/// Renaming moves the value and its time to live, and fails on missing keys.
+#[tokio::test]
+async fn rename_moves_values() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client
+        .set_expires("hello", "world".into(), Duration::from_secs(60))
+        .await
+        .unwrap();
+    client.set("other", "value".into()).await.unwrap();
+
+    client.rename("hello", "other").await.unwrap();
+    assert!(client.get("hello").await.unwrap().is_none());
+    assert_eq!(b"world", &client.get("other").await.unwrap().unwrap()[..]);
+    assert!(client.ttl("other").await.unwrap() > 0);
+
+    // Renaming a key onto itself does nothing.
+    client.rename("other", "other").await.unwrap();
+    assert_eq!(b"world", &client.get("other").await.unwrap().unwrap()[..]);
+
+    match client.rename("missing", "other").await {
+        Err(MiniRedisConnectionError::ErrorReply(msg)) => assert_eq!("ERR no such key", msg),
+        res => panic!("unexpected response: {:?}", res),
+    }
+}
+
+/// Renaming without overwriting only succeeds if the new key is missing.
+#[tokio::test]
+async fn rename_nx_keeps_existing_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    client.set("other", "value".into()).await.unwrap();
+
+    assert!(!client.rename_nx("hello", "other").await.unwrap());
+    assert_eq!(b"value", &client.get("other").await.unwrap().unwrap()[..]);
+    assert!(!client.rename_nx("hello", "hello").await.unwrap());
+
+    assert!(client.rename_nx("hello", "new").await.unwrap());
+    assert!(client.get("hello").await.unwrap().is_none());
+    assert_eq!(b"world", &client.get("new").await.unwrap().unwrap()[..]);
+}
+
+/// Strings are reported as such, and missing keys as `none`.
+#[tokio::test]
+async fn key_type_of_strings_and_missing_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+
+    assert_eq!("string", client.key_type("hello").await.unwrap());
+    assert_eq!("none", client.key_type("missing").await.unwrap());
+}
+
+/// The length is counted in bytes, not characters.
+#[tokio::test]
+async fn strlen_counts_bytes() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "héllo".into()).await.unwrap();
+    client
+        .set("binary", Bytes::from_static(b"\x00\xff"))
+        .await
+        .unwrap();
+
+    assert_eq!(6, client.strlen("hello").await.unwrap());
+    assert_eq!(2, client.strlen("binary").await.unwrap());
+    assert_eq!(0, client.strlen("missing").await.unwrap());
+}
+
+/// Appending is binary safe, and creates missing keys.
+#[tokio::test]
+async fn append_binary_values() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(
+        2,
+        client
+            .append("hello", Bytes::from_static(b"\xff\x00"))
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        5,
+        client
+            .append("hello", Bytes::from_static(b"\xc3\xa9!"))
+            .await
+            .unwrap()
+    );
+
+    let value = client.get("hello").await.unwrap().unwrap();
+    assert_eq!(b"\xff\x00\xc3\xa9!", &value[..]);
+}
+
+/// Counters start at 0, and non integer values are rejected with the Redis
+/// error.
+#[tokio::test]
+async fn incr_and_decr() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(1, client.incr("counter").await.unwrap());
+    assert_eq!(2, client.incr("counter").await.unwrap());
+    assert_eq!(1, client.decr("counter").await.unwrap());
+    assert_eq!(-1, client.decr("other").await.unwrap());
+    assert_eq!(b"1", &client.get("counter").await.unwrap().unwrap()[..]);
+
+    client.set("hello", "world".into()).await.unwrap();
+    match client.incr("hello").await {
+        Err(MiniRedisConnectionError::ErrorReply(msg)) => {
+            assert_eq!("ERR value is not an integer or out of range", msg)
+        }
+        res => panic!("unexpected response: {:?}", res),
+    }
+
+    client
+        .set("max", i64::MAX.to_string().into())
+        .await
+        .unwrap();
+    assert!(client.incr("max").await.is_err());
+    assert_eq!(i64::MAX - 1, client.decr("max").await.unwrap());
+}
+
+/// Deltas may be negative, and results out of the `i64` range are rejected.
+#[tokio::test]
+async fn incr_by_and_decr_by() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(10, client.incr_by("counter", 10).await.unwrap());
+    assert_eq!(7, client.incr_by("counter", -3).await.unwrap());
+    assert_eq!(2, client.decr_by("counter", 5).await.unwrap());
+    assert_eq!(4, client.decr_by("counter", -2).await.unwrap());
+
+    assert!(client.incr_by("counter", i64::MAX).await.is_err());
+    match client.decr_by("counter", i64::MIN).await {
+        Err(MiniRedisConnectionError::ErrorReply(msg)) => {
+            assert_eq!("ERR decrement would overflow", msg)
+        }
+        res => panic!("unexpected response: {:?}", res),
+    }
+    assert_eq!(4, client.incr_by("counter", 0).await.unwrap());
+}
+
+/// Concurrent increments from several connections are not lost.
+#[tokio::test]
+async fn concurrent_incr() {
+    let (addr, _) = start_server().await;
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            tokio::spawn(async move {
+                let mut client = client::connect(addr).await.unwrap();
+                for _ in 0..100 {
+                    client.incr("counter").await.unwrap();
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(401, client.incr("counter").await.unwrap());
+}
+
+/// Deleting keys returns the number of keys removed, ignoring missing ones.
+#[tokio::test]
+async fn del_removes_keys() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    client.set("foo", "bar".into()).await.unwrap();
+
+    assert_eq!(2, client.del(&["hello", "foo", "missing"]).await.unwrap());
+    assert_eq!(None, client.get("hello").await.unwrap());
+    assert_eq!(0, client.del(&["hello"]).await.unwrap());
+}
+
+/// Existing keys are counted once per occurrence in the request.
+#[tokio::test]
+async fn exists_counts_duplicates() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+
+    assert_eq!(
+        2,
+        client.exists(&["hello", "hello", "missing"]).await.unwrap()
+    );
+}
+
 /// similar to the "hello world" style test, But this time
 /// a single channel subscription will be tested instead
 #[tokio::test]
@@ -58,6 +542,26 @@ async fn receive_message_subscribed_channel() {
     assert_eq!(b"world", &message.content[..])
 }
 
+/// Binary messages are received byte for byte.
+#[tokio::test]
+async fn receive_binary_message() {
+    let (addr, _) = start_server().await;
+
+    let client = client::connect(addr).await.unwrap();
+    let mut subscriber = client.subscribe(vec!["hello".into()]).await.unwrap();
+
+    let content = Bytes::from(vec![0u8, 255, b'\n']);
+    let published = content.clone();
+    tokio::spawn(async move {
+        let mut client = client::connect(addr).await.unwrap();
+        client.publish("hello", published).await.unwrap()
+    });
+
+    let message = subscriber.next_message().await.unwrap().unwrap();
+    assert_eq!("hello", &message.channel);
+    assert_eq!(content, message.content);
+}
+
 /// test that a client gets messages from multiple subscribed channels
 #[tokio::test]
 async fn receive_message_multiple_subscribed_channels() {
@@ -104,6 +608,168 @@ async fn unsubscribes_from_channels() {
     assert_eq!(subscriber.get_subscribed().len(), 0);
 }
 
+/// test publishing to several channels at once, with subscribers on only some
+/// of them
+#[tokio::test]
+async fn mpublish_to_multiple_channels() {
+    let (addr, _) = start_server().await;
+
+    let client = client::connect(addr).await.unwrap();
+    let mut subscriber1 = client.subscribe(vec!["hello".into()]).await.unwrap();
+
+    let client = client::connect(addr).await.unwrap();
+    let mut subscriber2 = client
+        .subscribe(vec!["hello".into(), "world".into()])
+        .await
+        .unwrap();
+
+    let mut client = client::connect(addr).await.unwrap();
+    let counts = client
+        .mpublish(&[
+            ("hello", "a".into()),
+            ("world", "b".into()),
+            ("nobody", "c".into()),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(vec![2, 1, 0], counts);
+
+    let message = subscriber1.next_message().await.unwrap().unwrap();
+    assert_eq!("hello", &message.channel);
+    assert_eq!(b"a", &message.content[..]);
+
+    // Messages from different channels may be delivered in any order
+    let mut received = vec![];
+    for _ in 0..2 {
+        let message = subscriber2.next_message().await.unwrap().unwrap();
+        received.push((message.channel, message.content));
+    }
+    received.sort();
+    assert_eq!(
+        vec![
+            ("hello".to_string(), "a".into()),
+            ("world".to_string(), "b".into())
+        ],
+        received
+    );
+}
+
+/// the traffic counters of a connection advance as commands are issued, and
+/// are reported by CLIENT LIST
+#[tokio::test]
+async fn client_list_reports_connection_stats() {
+    let (addr, _) = start_server().await;
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.set("hello", "world".into()).await.unwrap();
+    client.get("hello").await.unwrap();
+
+    let list = client.client_list().await.unwrap();
+    let line = list.lines().next().unwrap();
+    let field = |name: &str| -> u64 {
+        line.split(' ')
+            .find_map(|pair| pair.strip_prefix(&format!("{}=", name)[..]))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+
+    // SET and GET have been applied, CLIENT LIST is being applied.
+    assert_eq!(2, field("tot-cmds"));
+    assert_eq!(3, field("tot-frames"));
+    assert!(field("tot-net-in") > 0);
+    // `+OK\r\n` and `$5\r\nworld\r\n`
+    assert_eq!(16, field("tot-net-out"));
+
+    // The counters keep advancing
+    client.ping(None).await.unwrap();
+    let list = client.client_list().await.unwrap();
+    assert!(list.contains("tot-cmds=4"));
+}
+
+/// polling a subscriber returns a buffered message, then reports that no
+/// message is available without waiting
+#[tokio::test]
+async fn try_next_message_does_not_wait() {
+    let (addr, _) = start_server().await;
+
+    let client = client::connect(addr).await.unwrap();
+    let mut subscriber = client.subscribe(vec!["hello".into()]).await.unwrap();
+    assert!(subscriber.try_next_message().unwrap().is_none());
+
+    let mut client = client::connect(addr).await.unwrap();
+    client.publish("hello", "world".into()).await.unwrap();
+
+    // The message is forwarded to the subscriber asynchronously
+    let message = loop {
+        if let Some(message) = subscriber.try_next_message().unwrap() {
+            break message;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+    assert_eq!("hello", &message.channel);
+    assert_eq!(b"world", &message.content[..]);
+
+    assert!(subscriber.try_next_message().unwrap().is_none());
+}
+
+/// the cluster introspection stubs report a standalone node
+#[tokio::test]
+async fn cluster_info_reports_disabled() {
+    let (addr, _) = start_server().await;
+    let mut client = client::connect(addr).await.unwrap();
+
+    let info = client.cluster_info().await.unwrap();
+    assert!(info.contains("cluster_enabled:0\r\n"));
+
+    let id = client.cluster_myid().await.unwrap();
+    assert_eq!(40, id.len());
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+
+    // The node id is stable across connections
+    let mut client = client::connect(addr).await.unwrap();
+    assert_eq!(id, client.cluster_myid().await.unwrap());
+}
+
+/// Error replies expose their error code.
+#[tokio::test]
+async fn error_reply_exposes_error_code() {
+    // A fake server replying to any command with a WRONGTYPE error.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0; 64];
+        let _ = socket.read(&mut buf).await.unwrap();
+        socket
+            .write_all(b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n")
+            .await
+            .unwrap();
+    });
+
+    let mut client = client::connect(addr).await.unwrap();
+    let err = client.get("hello").await.unwrap_err();
+    assert_eq!(Some("WRONGTYPE"), err.error_code());
+    assert!(err.is_wrongtype());
+    assert!(!err.is_noauth());
+}
+
+/// A client with tiny buffers still handles values larger than its buffers.
+#[tokio::test]
+async fn client_builder_with_custom_buffer_sizes() {
+    let (addr, _) = start_server().await;
+    let mut client = client::ClientBuilder::new()
+        .read_buffer_size(16)
+        .write_buffer_size(16)
+        .connect(addr)
+        .await
+        .unwrap();
+
+    let value = Bytes::from(vec![b'x'; 1024]);
+    client.set("hello", value.clone()).await.unwrap();
+    assert_eq!(Some(value), client.get("hello").await.unwrap());
+}
+
 async fn start_server() -> (SocketAddr, JoinHandle<()>) {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
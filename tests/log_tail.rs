@@ -0,0 +1,56 @@
+//! The log tail is tested in its own binary, as it installs the global logger.
+
+use std::collections::HashSet;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{self, Duration};
+
+use mini_redis::config::ServerConfig;
+use mini_redis::{logger, server};
+
+// Records logged after LOGTAIL are sent to the tailing connection.
+#[tokio::test]
+async fn log_tail_receives_warnings() {
+    logger::init();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        debug_commands: true,
+        strict_channels: true,
+        declared_channels: HashSet::new(),
+        ..Default::default()
+    };
+    tokio::spawn(async move {
+        server::run_with_config(listener, tokio::signal::ctrl_c(), config).await
+    });
+
+    let mut tail = TcpStream::connect(addr).await.unwrap();
+    tail.write_all(b"*2\r\n$7\r\nLOGTAIL\r\n$4\r\nwarn\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 5];
+    tail.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    // Subscribing to an undeclared channel logs a warning.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"*2\r\n$9\r\nSUBSCRIBE\r\n$4\r\nnope\r\n")
+        .await
+        .unwrap();
+
+    let expected = "$4\r\nwarn\r\n$66\r\nmini_redis::cmd::subscribe - subscribe to undeclared channel: nope\r\n";
+    let mut received = vec![];
+    time::timeout(Duration::from_secs(1), async {
+        while !String::from_utf8_lossy(&received).contains(expected) {
+            let mut buf = [0; 256];
+            let n = tail.read(&mut buf).await.unwrap();
+            assert_ne!(0, n);
+            received.extend_from_slice(&buf[..n]);
+        }
+    })
+    .await
+    .unwrap();
+}
@@ -1,9 +1,12 @@
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::time::{self, Duration};
 
+use mini_redis::config::{ConnectionObserver, DisconnectReason, ServerConfig};
+use mini_redis::error::MiniRedisServerError;
 use mini_redis::server;
 
 /// A basic "hello world" style test. A server instance is started in a
@@ -352,6 +355,270 @@ async fn send_error_unknown_command() {
     assert_eq!(b"-err unknown command \'foo\'\r\n", &response);
 }
 
+// A malformed frame is reported to the client before the connection is closed.
+#[tokio::test]
+async fn send_error_invalid_multibulk_length() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream.write_all(b"*x\r\n").await.unwrap();
+
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    assert_eq!(
+        &b"-ERR Protocol error: invalid multibulk length\r\n"[..],
+        &response[..]
+    );
+}
+
+// Commands sent as lines of text, as by `nc`, are served like RESP arrays.
+#[tokio::test]
+async fn inline_commands() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream.write_all(b"set foo bar\n").await.unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    // Blank lines are skipped, and RESP frames are still accepted.
+    stream
+        .write_all(b"\r\nget foo\r\n*1\r\n$4\r\nPING\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 16];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$3\r\nbar\r\n+PONG\r\n", &response);
+}
+
+// Any number of blank lines is skipped without exhausting the stack.
+#[tokio::test]
+async fn inline_commands_after_many_blank_lines() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let mut request = vec![b'\n'; 1_000_000];
+    request.extend_from_slice(b"PING\r\n");
+    stream.write_all(&request).await.unwrap();
+
+    let mut response = [0; 7];
+    time::timeout(Duration::from_secs(5), stream.read_exact(&mut response))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+}
+
+// A command with invalid arguments is reported to the client, and the
+// connection keeps being served.
+#[tokio::test]
+async fn send_error_invalid_integer_argument() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nEX\r\n$3\r\nten\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 46];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"-ERR value is not an integer or out of range\r\n"[..],
+        &response[..]
+    );
+
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+}
+
+// Subscribing past the per-connection limit is rejected, while the existing
+// subscriptions keep receiving messages.
+#[tokio::test]
+async fn subscribe_past_max_subscriptions() {
+    let addr = start_server_with_config(ServerConfig {
+        max_subscriptions_per_connection: Some(1),
+        ..Default::default()
+    })
+    .await;
+
+    let mut sub = TcpStream::connect(addr).await.unwrap();
+    sub.write_all(b"*3\r\n$9\r\nSUBSCRIBE\r\n$5\r\nhello\r\n$3\r\nfoo\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 34];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"*3\r\n$9\r\nsubscribe\r\n$5\r\nhello\r\n:1\r\n"[..],
+        &response[..]
+    );
+    let mut response = [0; 32];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(&b"-ERR max subscriptions reached\r\n"[..], &response[..]);
+
+    let mut publisher = TcpStream::connect(addr).await.unwrap();
+    publisher
+        .write_all(b"*3\r\n$7\r\nPUBLISH\r\n$5\r\nhello\r\n$5\r\nworld\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 4];
+    publisher.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":1\r\n", &response);
+
+    let mut response = [0; 39];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"*3\r\n$7\r\nmessage\r\n$5\r\nhello\r\n$5\r\nworld\r\n"[..],
+        &response[..]
+    );
+}
+
+// Commands sent with too few or too many arguments are answered with an error,
+// and the connection keeps being served.
+#[tokio::test]
+async fn send_error_wrong_number_of_arguments() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let cases: [(&[u8], &[u8]); 4] = [
+        // Too few arguments
+        (
+            b"*1\r\n$3\r\nGET\r\n",
+            b"-ERR wrong number of arguments for 'get' command\r\n",
+        ),
+        (
+            b"*2\r\n$3\r\nSET\r\n$5\r\nhello\r\n",
+            b"-ERR wrong number of arguments for 'set' command\r\n",
+        ),
+        // Too many arguments
+        (
+            b"*4\r\n$7\r\nPUBLISH\r\n$5\r\nhello\r\n$5\r\nworld\r\n$1\r\n!\r\n",
+            b"-ERR wrong number of arguments for 'publish' command\r\n",
+        ),
+        (
+            b"*3\r\n$4\r\nPING\r\n$5\r\nhello\r\n$5\r\nworld\r\n",
+            b"-ERR wrong number of arguments for 'ping' command\r\n",
+        ),
+    ];
+
+    for (request, expected) in cases {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(expected, &response[..]);
+    }
+
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+}
+
+// PING replies with `PONG` or echoes its message in normal mode, and with a
+// `pong` array in subscribe mode.
+#[tokio::test]
+async fn ping_in_normal_and_subscribe_mode() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let cases: [(&[u8], &[u8]); 2] = [
+        (b"*1\r\n$4\r\nPING\r\n", b"+PONG\r\n"),
+        (b"*2\r\n$4\r\nPING\r\n$5\r\nhello\r\n", b"$5\r\nhello\r\n"),
+    ];
+    for (request, expected) in cases {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(expected, &response[..]);
+    }
+
+    stream
+        .write_all(b"*2\r\n$9\r\nsubscribe\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 34];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"*3\r\n$9\r\nsubscribe\r\n$5\r\nhello\r\n:1\r\n"[..],
+        &response[..]
+    );
+
+    let cases: [(&[u8], &[u8]); 2] = [
+        (b"*1\r\n$4\r\nPING\r\n", b"*2\r\n$4\r\npong\r\n$0\r\n\r\n"),
+        (
+            b"*2\r\n$4\r\nPING\r\n$5\r\nhello\r\n",
+            b"*2\r\n$4\r\npong\r\n$5\r\nhello\r\n",
+        ),
+    ];
+    for (request, expected) in cases {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(expected, &response[..]);
+    }
+}
+
+// An invalid command sent while subscribed is reported to the client, and the
+// subscriptions keep being served.
+#[tokio::test]
+async fn send_error_invalid_command_while_subscribed() {
+    let addr = start_server().await;
+
+    let mut publisher = TcpStream::connect(addr).await.unwrap();
+
+    let mut sub = TcpStream::connect(addr).await.unwrap();
+    sub.write_all(b"*2\r\n$9\r\nSUBSCRIBE\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 34];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"*3\r\n$9\r\nsubscribe\r\n$5\r\nhello\r\n:1\r\n"[..],
+        &response[..]
+    );
+
+    // `SUBSCRIBE` requires at least one channel
+    sub.write_all(b"*1\r\n$9\r\nSUBSCRIBE\r\n").await.unwrap();
+
+    let expected = b"-ERR wrong number of arguments for 'subscribe' command\r\n";
+    let mut response = vec![0; expected.len()];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(&expected[..], &response[..]);
+
+    publisher
+        .write_all(b"*3\r\n$7\r\nPUBLISH\r\n$5\r\nhello\r\n$5\r\nworld\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 4];
+    publisher.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":1\r\n", &response);
+
+    let mut response = [0; 39];
+    sub.read_exact(&mut response).await.unwrap();
+    assert_eq!(
+        &b"*3\r\n$7\r\nmessage\r\n$5\r\nhello\r\n$5\r\nworld\r\n"[..],
+        &response[..]
+    );
+}
+
 // In this case we test that server Responds with an Error message if a client
 // sends an GET or SET command after a SUBSCRIBE
 #[tokio::test]
@@ -396,11 +663,1038 @@ async fn send_error_get_set_after_subscribe() {
     assert_eq!(b"-err unknown command \'get\'\r\n", &response);
 }
 
-async fn start_server() -> SocketAddr {
-    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-    let addr = listener.local_addr().unwrap();
+// In strict channel mode, publishing to an undeclared channel is rejected
+// while declared channels keep working.
+#[tokio::test]
+async fn strict_channels_reject_undeclared() {
+    let config = ServerConfig {
+        strict_channels: true,
+        declared_channels: vec!["hello".to_string()].into_iter().collect(),
+        ..Default::default()
+    };
+    let addr = start_server_with_config(config).await;
 
-    tokio::spawn(async move { server::run(listener, tokio::signal::ctrl_c()).await });
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    // Publish to a declared channel
+    stream
+        .write_all(b"*3\r\n$7\r\nPUBLISH\r\n$5\r\nhello\r\n$5\r\nworld\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 4];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":0\r\n", &response);
+
+    // Publish to an undeclared channel
+    stream
+        .write_all(b"*3\r\n$7\r\nPUBLISH\r\n$4\r\nhelo\r\n$5\r\nworld\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 29];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"-ERR unknown channel 'helo'\r\n", &response);
+}
+
+// Without the background purge task, expired keys are still never returned.
+#[tokio::test]
+async fn key_value_timeout_without_purge_task() {
+    let config = ServerConfig {
+        disable_purge_task: true,
+        ..Default::default()
+    };
+    let addr = start_server_with_config(config).await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    // Set a key expiring after 100ms
+    stream
+        .write_all(
+            b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n\
+                     +PX\r\n:100\r\n",
+        )
+        .await
+        .unwrap();
+
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    // Wait for the key to expire
+    time::sleep(Duration::from_millis(200)).await;
+
+    // Get the key, data is missing
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$-1\r\n", &response);
+}
+
+// A key without its value is rejected, and no key is set.
+#[tokio::test]
+async fn mset_rejects_mismatched_pairs() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(b"*4\r\n$4\r\nMSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$3\r\nfoo\r\n")
+        .await
+        .unwrap();
+
+    let expected = b"-ERR `MSET` expects key and value pairs\r\n";
+    let mut response = [0; 41];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(expected, &response);
+
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$-1\r\n", &response);
+}
+
+// An expired key which has not been purged yet does not exist.
+#[tokio::test]
+async fn exists_ignores_unpurged_expired_keys() {
+    let config = ServerConfig {
+        disable_purge_task: true,
+        ..Default::default()
+    };
+    let addr = start_server_with_config(config).await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    // Set a key expiring after 100ms
+    stream
+        .write_all(
+            b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n\
+                     +PX\r\n:100\r\n",
+        )
+        .await
+        .unwrap();
+
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    stream
+        .write_all(b"*2\r\n$6\r\nEXISTS\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 4];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":1\r\n", &response);
+
+    // Wait for the key to expire
+    time::sleep(Duration::from_millis(200)).await;
+
+    stream
+        .write_all(b"*2\r\n$6\r\nEXISTS\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 4];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":0\r\n", &response);
+}
+
+// An expiration instant which would overflow is rejected and leaves the key
+// alone, while a negative time to live deletes the key.
+#[tokio::test]
+async fn expire_overflowing_and_negative_ttl() {
+    let addr = start_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(b"*3\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    stream
+        .write_all(b"*3\r\n$6\r\nEXPIRE\r\n$5\r\nhello\r\n$19\r\n9223372036854775807\r\n")
+        .await
+        .unwrap();
+    let expected = b"-ERR invalid expire time in 'expire' command\r\n";
+    let mut response = vec![0; expected.len()];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(&expected[..], &response[..]);
+
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 11];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$5\r\nworld\r\n", &response);
+
+    stream
+        .write_all(b"*3\r\n$6\r\nEXPIRE\r\n$5\r\nhello\r\n$2\r\n-1\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 4];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":1\r\n", &response);
+
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$-1\r\n", &response);
+}
+
+// SET rejects a zero time to live, as well as one whose expiration instant
+// would overflow, without storing the key.
+#[tokio::test]
+async fn set_overflowing_and_zero_ttl() {
+    let addr = start_server_with_config(ServerConfig {
+        ttl_jitter: Some(10.0),
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let requests: [&[u8]; 2] = [
+        b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nEX\r\n$20\r\n18446744073709551615\r\n",
+        b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nPX\r\n$1\r\n0\r\n",
+    ];
+    for request in requests {
+        stream.write_all(request).await.unwrap();
+        let expected = b"-ERR invalid expire time in 'set' command\r\n";
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(&expected[..], &response[..]);
+    }
+
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$-1\r\n", &response);
+}
+
+// With lazy expiry disabled, reading an expired key hides it without removing
+// it from the store.
+#[tokio::test]
+async fn expired_key_kept_on_read_without_lazy_expiry() {
+    for disable_lazy_expiry in [false, true] {
+        let addr = start_server_with_config(ServerConfig {
+            debug_commands: true,
+            disable_purge_task: true,
+            disable_lazy_expiry,
+            ..Default::default()
+        })
+        .await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        stream
+            .write_all(b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nPX\r\n$2\r\n50\r\n")
+            .await
+            .unwrap();
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+OK\r\n", &response);
+
+        // Wait for the key to expire
+        time::sleep(Duration::from_millis(100)).await;
+
+        stream
+            .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+            .await
+            .unwrap();
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"$-1\r\n", &response);
+
+        stream
+            .write_all(b"*2\r\n$5\r\nDEBUG\r\n$8\r\nKEYCOUNT\r\n")
+            .await
+            .unwrap();
+        let mut response = [0; 12];
+        stream.read_exact(&mut response).await.unwrap();
+        let expected: &[u8; 12] = if disable_lazy_expiry {
+            b"*2\r\n:1\r\n:0\r\n"
+        } else {
+            b"*2\r\n:0\r\n:0\r\n"
+        };
+        assert_eq!(expected, &response);
+    }
+}
+
+// A connection sending a long pipeline yields to the other tasks every
+// `yield_every` commands. The test runs on a single thread, and counts how
+// often a spinning task gets to run while the replies to the pipeline are
+// written.
+#[tokio::test]
+async fn pipelined_connection_does_not_starve_others() {
+    const COMMANDS: usize = 2_000;
+
+    let config = ServerConfig {
+        yield_every: 16,
+        ..Default::default()
+    };
+    let addr = start_server_with_config(config).await;
+
+    // The whole pipeline is sent before the server reads anything, so reads
+    // are always ready and the connection never has to wait for the peer.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut pipeline = vec![];
+    for _ in 0..COMMANDS {
+        pipeline.extend_from_slice(b"*1\r\n$4\r\nPING\r\n");
+    }
+    stream.write_all(&pipeline).await.unwrap();
+
+    let ticks = Arc::new(Mutex::new(0_usize));
+    let spinner = tokio::spawn({
+        let ticks = ticks.clone();
+        async move {
+            loop {
+                *ticks.lock().unwrap() += 1;
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+
+    // Once the first replies are in, the other task keeps being polled until
+    // the whole pipeline is processed.
+    let mut response = vec![0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    let first = *ticks.lock().unwrap();
+
+    let mut response = vec![0; 7 * (COMMANDS - 1)];
+    stream.read_exact(&mut response).await.unwrap();
+    let last = *ticks.lock().unwrap();
+    spinner.abort();
+
+    assert!(response.chunks(7).all(|reply| reply == b"+PONG\r\n"));
+    assert!(
+        last - first >= COMMANDS / 16 / 2,
+        "only {} ticks while the pipeline was processed",
+        last - first
+    );
+}
+
+// When enabled, the last commands received are returned by DEBUG HISTORY, in
+// the order they were received.
+#[tokio::test]
+async fn debug_history_lists_last_commands() {
+    let addr = start_server_with_config(ServerConfig {
+        debug_commands: true,
+        command_history: 3,
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    // The PING is evicted from the history by the later commands
+    let requests: [&[u8]; 3] = [
+        b"*1\r\n$4\r\nPING\r\n",
+        b"*3\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n",
+        b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n",
+    ];
+    let responses: [&[u8]; 3] = [b"+PONG\r\n", b"+OK\r\n", b"$5\r\nworld\r\n"];
+    for (request, expected) in requests.iter().zip(responses) {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(expected, &response[..]);
+    }
+
+    stream
+        .write_all(b"*2\r\n$5\r\nDEBUG\r\n$7\r\nHISTORY\r\n")
+        .await
+        .unwrap();
+
+    let mut response = vec![];
+    while !response.ends_with(b"DEBUG HISTORY\r\n") {
+        stream.read_buf(&mut response).await.unwrap();
+    }
+    let response = String::from_utf8(response).unwrap();
+    let local_addr = stream.local_addr().unwrap().to_string();
+
+    assert!(response.starts_with("*3\r\n"));
+    assert!(!response.contains("PING"));
+    let set = response.find("SET hello world").unwrap();
+    let get = response.find("GET hello").unwrap();
+    let debug = response.find("DEBUG HISTORY").unwrap();
+    assert!(set < get && get < debug);
+    assert_eq!(3, response.matches(&local_addr[..]).count());
+}
+
+// A server bound to the IPv6 unspecified address accepts both IPv4 and IPv6
+// connections.
+#[tokio::test]
+async fn dual_stack_bind() {
+    let listener = server::bind("[::]:0".parse().unwrap()).unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move { server::run(listener, tokio::signal::ctrl_c()).await });
+
+    for addr in ["127.0.0.1", "::1"] {
+        let addr = SocketAddr::new(addr.parse().unwrap(), port);
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+
+        let mut response = [0; 7];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+PONG\r\n", &response);
+    }
+}
+
+// With a command budget, KEYS and SCAN abort with an error on a keyspace too
+// large to iterate in time, releasing the store for the other commands.
+#[tokio::test]
+async fn keyspace_iteration_exceeding_budget_aborts() {
+    const KEYS: usize = 10_000;
+
+    let addr = start_server_with_config(ServerConfig {
+        command_budget: Some(Duration::from_nanos(1)),
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let mut request = format!("*{}\r\n$4\r\nMSET\r\n", 1 + 2 * KEYS).into_bytes();
+    for i in 0..KEYS {
+        let key = format!("key:{}", i);
+        request.extend_from_slice(format!("${}\r\n{}\r\n$1\r\nv\r\n", key.len(), key).as_bytes());
+    }
+    stream.write_all(&request).await.unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    let requests: [&[u8]; 2] = [
+        b"*2\r\n$4\r\nKEYS\r\n$1\r\n*\r\n",
+        b"*2\r\n$4\r\nSCAN\r\n$1\r\n0\r\n",
+    ];
+    for request in requests {
+        stream.write_all(request).await.unwrap();
+        let expected = b"-ERR command exceeded time budget\r\n";
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(&expected[..], &response[..]);
+    }
+
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$6\r\nkey:42\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$1\r\nv\r\n", &response);
+}
+
+// DEBUG KEYCOUNT counts expired keys which were not purged yet apart from live
+// ones, and is only allowed when debug commands are enabled.
+#[tokio::test]
+async fn debug_keycount_counts_unpurged_keys() {
+    let addr = start_server_with_config(ServerConfig {
+        debug_commands: true,
+        disable_purge_task: true,
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let requests: [&[u8]; 2] = [
+        b"*3\r\n$3\r\nSET\r\n$3\r\nfoo\r\n$3\r\nbar\r\n",
+        b"*5\r\n$3\r\nSET\r\n$5\r\nhello\r\n$5\r\nworld\r\n$2\r\nPX\r\n$1\r\n1\r\n",
+    ];
+    for request in requests {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+OK\r\n", &response);
+    }
+
+    // Wait for `hello` to expire, it is not purged as there is no purge task
+    time::sleep(Duration::from_millis(20)).await;
+
+    stream
+        .write_all(b"*2\r\n$5\r\nDEBUG\r\n$8\r\nKEYCOUNT\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"*2\r\n:2\r\n:1\r\n", &response);
+
+    // Debug commands are disabled by default
+    let addr = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(b"*2\r\n$5\r\nDEBUG\r\n$8\r\nKEYCOUNT\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 32];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(&b"-ERR DEBUG command not allowed\r\n"[..], &response[..]);
+}
+
+// DEBUG PURGE purges the expired keys right away and returns their number.
+#[tokio::test]
+async fn debug_purge_returns_purged_count() {
+    let addr = start_server_with_config(ServerConfig {
+        debug_commands: true,
+        disable_purge_task: true,
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let requests: [&[u8]; 4] = [
+        b"*5\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n$2\r\nPX\r\n$2\r\n50\r\n",
+        b"*5\r\n$3\r\nSET\r\n$1\r\nb\r\n$1\r\n2\r\n$2\r\nPX\r\n$2\r\n50\r\n",
+        b"*5\r\n$3\r\nSET\r\n$1\r\nc\r\n$1\r\n3\r\n$2\r\nPX\r\n$2\r\n50\r\n",
+        b"*3\r\n$3\r\nSET\r\n$1\r\nd\r\n$1\r\n4\r\n",
+    ];
+    for request in requests {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+OK\r\n", &response);
+    }
+
+    // Wait for the keys to expire
+    time::sleep(Duration::from_millis(100)).await;
+
+    stream
+        .write_all(b"*2\r\n$5\r\nDEBUG\r\n$5\r\nPURGE\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 4];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b":3\r\n", &response);
+
+    stream
+        .write_all(b"*2\r\n$5\r\nDEBUG\r\n$8\r\nKEYCOUNT\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"*2\r\n:1\r\n:1\r\n", &response);
+}
+
+// DEBUG DUMPHEX returns a value hex encoded, truncated past the dump limit.
+#[tokio::test]
+async fn debug_dumphex_encodes_binary_values() {
+    let addr = start_server_with_config(ServerConfig {
+        debug_commands: true,
+        debug_dump_limit: Some(4),
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let requests: [&[u8]; 2] = [
+        b"*3\r\n$3\r\nSET\r\n$5\r\nshort\r\n$3\r\n\x00\xff\n\r\n",
+        b"*3\r\n$3\r\nSET\r\n$4\r\nlong\r\n$7\r\n\x00\xffhello\r\n",
+    ];
+    for request in requests {
+        stream.write_all(request).await.unwrap();
+
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+OK\r\n", &response);
+    }
+
+    stream
+        .write_all(b"*3\r\n$5\r\nDEBUG\r\n$7\r\nDUMPHEX\r\n$5\r\nshort\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$6\r\n00ff0a\r\n", &response);
+
+    stream
+        .write_all(b"*3\r\n$5\r\nDEBUG\r\n$7\r\nDUMPHEX\r\n$4\r\nlong\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 18];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$11\r\n00ff6865...\r\n", &response);
+
+    stream
+        .write_all(b"*3\r\n$5\r\nDEBUG\r\n$7\r\nDUMPHEX\r\n$7\r\nmissing\r\n")
+        .await
+        .unwrap();
+
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"$-1\r\n", &response);
+}
+
+/// Records the connection lifecycle events.
+#[derive(Debug, Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<(&'static str, u64, Option<DisconnectReason>)>>,
+}
+
+impl ConnectionObserver for RecordingObserver {
+    fn on_connect(&self, _peer: SocketAddr, id: u64) {
+        self.events.lock().unwrap().push(("connect", id, None));
+    }
+
+    fn on_disconnect(&self, id: u64, reason: DisconnectReason) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(("disconnect", id, Some(reason)));
+    }
+}
+
+// The connection observer is notified of connects and disconnects, with
+// matching ids.
+#[tokio::test]
+async fn connection_observer_sees_lifecycle() {
+    let observer = Arc::new(RecordingObserver::default());
+    let addr = start_server_with_config(ServerConfig {
+        connection_observer: Some(observer.clone()),
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+    drop(stream);
+
+    let wait_for_events = |n| {
+        let observer = observer.clone();
+        time::timeout(Duration::from_secs(1), async move {
+            while observer.events.lock().unwrap().len() < n {
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+    };
+    wait_for_events(2).await.unwrap();
+
+    // Sending an invalid frame terminates the connection with an error.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"*x\r\n").await.unwrap();
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    wait_for_events(4).await.unwrap();
+
+    let events = observer.events.lock().unwrap();
+    let (first, second) = (events[0].1, events[2].1);
+    assert_ne!(first, second);
+    assert_eq!(
+        vec![
+            ("connect", first, None),
+            ("disconnect", first, Some(DisconnectReason::Closed)),
+            ("connect", second, None),
+            (
+                "disconnect",
+                second,
+                Some(DisconnectReason::Error(
+                    "Protocol error: invalid multibulk length".into()
+                ))
+            ),
+        ],
+        *events
+    );
+}
+
+// GETRANGE follows the Redis semantics: negative offsets count from the end,
+// offsets are clamped, and empty ranges or missing keys return an empty bulk.
+#[tokio::test]
+async fn getrange_edge_cases() {
+    let addr = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(b"*3\r\n$3\r\nSET\r\n$5\r\nhello\r\n$11\r\nHello World\r\n")
+        .await
+        .unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    let cases = [
+        ("hello", "0", "-1", "Hello World"),
+        ("hello", "-3", "-1", "rld"),
+        ("hello", "5", "2", ""),
+        ("hello", "0", "100", "Hello World"),
+        ("hello", "-100", "4", "Hello"),
+        ("hello", "-1", "-3", ""),
+        ("hello", "20", "30", ""),
+        ("missing", "0", "-1", ""),
+    ];
+    for (key, start, end, expected) in cases {
+        let request = format!(
+            "*4\r\n$8\r\nGETRANGE\r\n${}\r\n{}\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
+            key.len(),
+            key,
+            start.len(),
+            start,
+            end.len(),
+            end
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let expected = format!("${}\r\n{}\r\n", expected.len(), expected);
+        let mut response = vec![0; expected.len()];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(
+            expected.as_bytes(),
+            &response[..],
+            "GETRANGE {} {} {}",
+            key,
+            start,
+            end
+        );
+    }
+}
+
+// DEBUG BIGKEYS reports the largest keys, largest first.
+#[tokio::test]
+async fn debug_bigkeys_reports_largest_keys() {
+    let addr = start_server_with_config(ServerConfig {
+        debug_commands: true,
+        ..Default::default()
+    })
+    .await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    for (key, len) in [("a", 1), ("b", 100), ("c", 10)] {
+        let request = format!(
+            "*3\r\n$3\r\nSET\r\n$1\r\n{}\r\n${}\r\n{}\r\n",
+            key,
+            len,
+            "x".repeat(len)
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = [0; 5];
+        stream.read_exact(&mut response).await.unwrap();
+        assert_eq!(b"+OK\r\n", &response);
+    }
+
+    stream
+        .write_all(b"*3\r\n$5\r\nDEBUG\r\n$7\r\nBIGKEYS\r\n$1\r\n2\r\n")
+        .await
+        .unwrap();
+
+    let expected: &[u8] = b"*2\r\n*2\r\n$1\r\nb\r\n:101\r\n*2\r\n$1\r\nc\r\n:11\r\n";
+    let mut response = vec![0; expected.len()];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(expected, &response[..]);
+
+    // A huge count reports all the keys rather than preallocating for it.
+    stream
+        .write_all(b"*3\r\n$5\r\nDEBUG\r\n$7\r\nBIGKEYS\r\n$20\r\n18446744073709551615\r\n")
+        .await
+        .unwrap();
+
+    let expected: &[u8] =
+        b"*3\r\n*2\r\n$1\r\nb\r\n:101\r\n*2\r\n$1\r\nc\r\n:11\r\n*2\r\n$1\r\na\r\n:2\r\n";
+    let mut response = vec![0; expected.len()];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(expected, &response[..]);
+}
+
+// COMMAND INFO describes each command: name, arity, flags, first key, last key
+// and key step. Unknown commands are described by a null.
+#[tokio::test]
+async fn command_info_reports_arity_flags_and_keys() {
+    let addr = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream
+        .write_all(
+            b"*5\r\n$7\r\nCOMMAND\r\n$4\r\nINFO\r\n$3\r\nGET\r\n$6\r\nnosuch\r\n$3\r\nset\r\n",
+        )
+        .await
+        .unwrap();
+
+    let expected: &[u8] = b"*3\r\n\
+        *6\r\n$3\r\nget\r\n:2\r\n*2\r\n+readonly\r\n+fast\r\n:1\r\n:1\r\n:1\r\n\
+        $-1\r\n\
+        *6\r\n$3\r\nset\r\n:-3\r\n*2\r\n+write\r\n+denyoom\r\n:1\r\n:1\r\n:1\r\n";
+    let mut response = vec![0; expected.len()];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(expected, &response[..]);
+}
+
+// The server shuts down gracefully when any of its shutdown signals fires.
+#[tokio::test]
+async fn shutdown_on_any_signal() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (first_tx, first_rx) = tokio::sync::oneshot::channel::<()>();
+    let (second_tx, second_rx) = tokio::sync::oneshot::channel::<()>();
+    let signals: Vec<server::ShutdownSignal> = vec![
+        Box::pin(async move {
+            let _ = first_rx.await;
+        }),
+        Box::pin(async move {
+            let _ = second_rx.await;
+        }),
+    ];
+    let handle = tokio::spawn(server::run_with_signals(listener, signals));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+
+    second_tx.send(()).unwrap();
+
+    // The connection is closed and the server returns
+    let mut response = [0; 1];
+    assert_eq!(0, stream.read(&mut response).await.unwrap());
+    time::timeout(Duration::from_secs(1), handle)
+        .await
+        .unwrap()
+        .unwrap();
+    drop(first_tx);
+}
+
+// With several acceptors, concurrent connections are all served, and the
+// server still shuts down gracefully.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn multiple_acceptors() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = ServerConfig {
+        acceptors: 4,
+        ..Default::default()
+    };
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let handle = tokio::spawn(server::run_with_config(listener, shutdown_rx, config));
+
+    let clients: Vec<_> = (0..64)
+        .map(|_| {
+            tokio::spawn(async move {
+                let mut stream = TcpStream::connect(addr).await.unwrap();
+                stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+
+                let mut response = [0; 7];
+                stream.read_exact(&mut response).await.unwrap();
+                assert_eq!(b"+PONG\r\n", &response);
+            })
+        })
+        .collect();
+    for client in clients {
+        time::timeout(Duration::from_secs(1), client)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    shutdown_tx.send(()).unwrap();
+    time::timeout(Duration::from_secs(1), handle)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+// Connections past the limit are only served once an active connection
+// terminates.
+#[tokio::test]
+async fn max_connections_limits_served_connections() {
+    let addr = start_server_with_config(ServerConfig {
+        max_connections: Some(1),
+        ..Default::default()
+    })
+    .await;
+
+    let mut first = TcpStream::connect(addr).await.unwrap();
+    first.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    let mut response = [0; 7];
+    first.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+
+    // The second connection is established by the OS, but not served.
+    let mut second = TcpStream::connect(addr).await.unwrap();
+    second.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    let mut response = [0; 7];
+    assert!(
+        time::timeout(Duration::from_millis(100), second.read_exact(&mut response))
+            .await
+            .is_err()
+    );
+
+    drop(first);
+    time::timeout(Duration::from_secs(1), second.read_exact(&mut response))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(b"+PONG\r\n", &response);
+}
+
+// A limit of 0 connections is rejected rather than never serving anyone.
+#[tokio::test]
+async fn zero_max_connections_rejected() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = ServerConfig {
+        max_connections: Some(0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        config.validate(),
+        Err(MiniRedisServerError::InvalidConfig(_))
+    ));
+
+    let res = server::run_with_config(listener, std::future::pending::<()>(), config).await;
+    match res {
+        Err(MiniRedisServerError::InvalidConfig(reason)) => {
+            assert_eq!("max_connections must be at least 1", reason)
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+// Shutdown completes as soon as the connections terminated, without waiting
+// for the timeout.
+#[tokio::test]
+async fn shutdown_does_not_wait_for_idle_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = ServerConfig {
+        shutdown_timeout: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let handle = tokio::spawn(server::run_with_config(listener, shutdown_rx, config));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    let mut response = [0; 7];
+    stream.read_exact(&mut response).await.unwrap();
+
+    shutdown_tx.send(()).unwrap();
+    time::timeout(Duration::from_secs(1), handle)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+// A connection blocked writing to a client which does not read delays the
+// shutdown by the timeout only.
+#[tokio::test]
+async fn shutdown_stops_waiting_after_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = ServerConfig {
+        shutdown_timeout: Some(Duration::from_millis(200)),
+        ..Default::default()
+    };
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let handle = tokio::spawn(server::run_with_config(listener, shutdown_rx, config));
+
+    // Store a value larger than what the socket buffers can hold.
+    let value = vec![b'x'; 32 * 1024 * 1024];
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut request =
+        format!("*3\r\n$3\r\nSET\r\n$5\r\nhello\r\n${}\r\n", value.len()).into_bytes();
+    request.extend_from_slice(&value);
+    request.extend_from_slice(b"\r\n");
+    stream.write_all(&request).await.unwrap();
+    let mut response = [0; 5];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(b"+OK\r\n", &response);
+
+    // Request the value without reading the response.
+    stream
+        .write_all(b"*2\r\n$3\r\nGET\r\n$5\r\nhello\r\n")
+        .await
+        .unwrap();
+    time::sleep(Duration::from_millis(100)).await;
+
+    shutdown_tx.send(()).unwrap();
+    time::timeout(Duration::from_secs(2), handle)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+// A shutdown future which is already complete stops the server right away,
+// and the listener is closed.
+#[tokio::test]
+async fn shutdown_before_accepting() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = ServerConfig {
+        acceptors: 4,
+        stats_log_interval: Some(Duration::from_millis(10)),
+        ..Default::default()
+    };
+    time::timeout(
+        Duration::from_secs(1),
+        server::run_with_config(listener, std::future::ready(()), config),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert!(TcpStream::connect(addr).await.is_err());
+}
+
+async fn start_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move { server::run(listener, tokio::signal::ctrl_c()).await });
+
+    addr
+}
+
+async fn start_server_with_config(config: ServerConfig) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        server::run_with_config(listener, tokio::signal::ctrl_c(), config).await
+    });
 
     addr
 }